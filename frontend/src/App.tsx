@@ -26,9 +26,20 @@ interface ToolResult {
   content: string,
 }
 
+interface Thinking {
+  type: "thinking",
+  thinking: string,
+  signature?: string,
+}
+
+interface RedactedThinking {
+  type: "redacted_thinking",
+  data: string,
+}
+
 interface Message {
   role: "user" | "assistant",
-  content: (Text | ToolUse | ToolResult)[],
+  content: (Text | ToolUse | ToolResult | Thinking | RedactedThinking)[],
 }
 
 interface FileChange {
@@ -271,24 +282,21 @@ const App: React.FC = () => {
             content: [contentItem],
           },
         ]);
-        switch(contentItem.type) {
-          case "text":
-            break;
-          case "tool_use":
-            await handleSendMessage({
-              role: "assistant",
-              content: [contentItem],
-            });
-            break;
-          case "tool_result":
-            await handleSendMessage({
-              role: "user",
-              content: [contentItem]
-            });
-            break;
-          default:
-            break;
-        }
+      }
+
+      // The server runs all tool uses of a message at once and answers them in one message
+      const toolUses = data.message.content.filter((item: ToolUse | ToolResult) => item.type === "tool_use");
+      const toolResults = data.message.content.filter((item: ToolUse | ToolResult) => item.type === "tool_result");
+      if (toolUses.length > 0) {
+        await handleSendMessage({
+          role: "assistant",
+          content: toolUses,
+        });
+      } else if (toolResults.length > 0) {
+        await handleSendMessage({
+          role: "user",
+          content: toolResults,
+        });
       }
     } catch (error: any) {
       console.error(error);
@@ -308,6 +316,36 @@ const App: React.FC = () => {
     }
   }, [recursiveCallCount, isProcessing, initialLoadComplete, messages]);
 
+  // Commands the command policy asks about wait on the server until they are answered
+  useEffect(() => {
+    if (!isProcessing) {
+      return;
+    }
+    const pollApprovals = async () => {
+      try {
+        const response = await fetch(`${window.SERVER_URL}/approvals`);
+        if (!response.ok) {
+          throw new Error('Failed to fetch approvals');
+        }
+        const approvals = await response.json();
+        for (const approval of approvals) {
+          const approved = window.confirm(`The model wants to run:\n\n${approval.command}\n\nAllow it?`);
+          await fetch(`${window.SERVER_URL}/approve`, {
+            method: 'POST',
+            headers: {
+              'Content-Type': 'application/json',
+            },
+            body: JSON.stringify({ id: approval.id, approved }),
+          });
+        }
+      } catch (error) {
+        console.error('Error fetching approvals:', error);
+      }
+    };
+    const interval = setInterval(pollApprovals, 1000);
+    return () => clearInterval(interval);
+  }, [isProcessing]);
+
   const handleClearChat = async (_e: any) => {
     try {
       const response = await fetch(`${window.SERVER_URL}/clear`, {
@@ -353,6 +391,14 @@ const App: React.FC = () => {
                 >
                   {"Using tool: " + message.content[0].name}
                 </div>
+              case "thinking":
+                return <details
+                  key={index}
+                  className="message thinking-msg"
+                >
+                  <summary>Thinking</summary>
+                  {message.content[0].thinking}
+                </details>
               default:
                 return null;
             }
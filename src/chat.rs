@@ -1,123 +1,297 @@
-use std::process::Command;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
-use tokenizers::Tokenizer;
+use futures::future::{self, abortable, AbortHandle};
+use futures::StreamExt;
+use serde::Serialize;
+use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
     inference::{
-        types::{ContentItem, Message, ModelResponse, Role, Inference},
-        AnthropicInference,
-        OpenAIInference,
-        DeepSeekInference,
-        AWSBedrockInference,
+        retry::{is_retryable, with_retry, RetryProgress},
+        registry::ProviderRegistry,
+        tokens::{self, ContextCounter},
+        types::{ContentItem, EventStream, ImageSource, InferenceError, Message, ModelResponse, Role, Inference, StreamEvent},
     },
     tree::GitTree,
+    usage::SessionUsage,
+    tools::{Approvals, CommandBlocked, ToolContext, ToolRegistry},
     config::ProjectConfig
 };
 
-static TOKENIZER_JSON: &[u8] = include_bytes!("../tokenizers/gpt2.json");
+/// Reads an image from a path relative to the project root, which the file tools could
+/// read as well.
+fn load_image(ctx: &ToolContext, path: &str) -> Result<ImageSource, anyhow::Error> {
+    let full_path = ctx.resolve(path)?;
+    let media_type = mime_guess::from_path(&full_path).first_raw().unwrap_or_default();
+    let bytes = std::fs::read(&full_path)?;
+    Ok(ImageSource::from_bytes(media_type, &bytes)?)
+}
 
-pub enum InferenceProvider {
-    Anthropic(AnthropicInference),
-    OpenAI(OpenAIInference),
-    DeepSeek(DeepSeekInference),
-    Bedrock(AWSBedrockInference),
+/// Progress of a chat turn, forwarded to streaming clients as Server-Sent Events.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatEvent {
+    TextDelta { text: String },
+    /// Reasoning the model does before answering, kept apart from `TextDelta` so clients
+    /// can show it collapsed
+    ThinkingDelta { thinking: String },
+    ToolUseStart { id: String, name: String },
+    ToolUseInputDelta { id: String, partial_json: String },
+    ToolUse { tool_use: ContentItem },
+    ToolResult { message: Message },
+    Message { message: Message },
+    /// The model call failed and will be retried, output streamed so far should be discarded
+    Retry(RetryProgress),
+    /// The provider is still failing after its retries and `model` takes over, output
+    /// streamed so far should be discarded
+    Fallback { model: String, error: String },
+    Error { error: serde_json::Value, error_type: String, status_code: u16 },
 }
 
-impl InferenceProvider {
-    async fn query_model(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, anyhow::Error> {
-        match self {
-            InferenceProvider::Anthropic(inference) => inference.query_model(messages, system_message)
-                .await
-                .map_err(|e| anyhow::anyhow!("Anthropic Inference Error: {}", e)),
-            InferenceProvider::OpenAI(inference) => inference.query_model(messages, system_message)
-                .await
-                .map_err(|e| anyhow::anyhow!("OpenAI Inference Error: {}", e)),
-            InferenceProvider::DeepSeek(inference) => inference.query_model(messages, system_message)
-                .await
-                .map_err(|e| anyhow::anyhow!("DeepSeek Inference Error: {}", e)),
-            InferenceProvider::Bedrock(inference) => inference.query_model(messages, system_message)
-                .await
-                .map_err(|e| anyhow::anyhow!("Bedrock Inference Error: {}", e)),
+/// Drains a model stream, forwarding deltas to `events` and returning the assembled
+/// response from its final event.
+async fn collect_response(
+    mut stream: EventStream,
+    events: Option<&UnboundedSender<ChatEvent>>,
+) -> Result<ModelResponse, InferenceError> {
+    while let Some(event) = stream.next().await {
+        let chat_event = match event? {
+            StreamEvent::Done { response } => return Ok(response),
+            StreamEvent::TextDelta { text } => ChatEvent::TextDelta { text },
+            StreamEvent::ThinkingDelta { thinking } => ChatEvent::ThinkingDelta { thinking },
+            StreamEvent::ToolUseStart { id, name } => ChatEvent::ToolUseStart { id, name },
+            StreamEvent::ToolUseInputDelta { id, partial_json } => ChatEvent::ToolUseInputDelta { id, partial_json },
+            StreamEvent::ToolUse { tool_use } => ChatEvent::ToolUse { tool_use },
+        };
+        if let Some(events) = events {
+            // The client going away must not abort the turn, the history still needs the response
+            let _ = events.send(chat_event);
         }
     }
+    Err(InferenceError::InvalidResponse("Model stream ended without a response".to_string()))
+}
+
+/// Whether the history can start at `message`, a plain user message.
+fn starts_turn(message: &Message) -> bool {
+    message.role == Role::User
+        && !message.content.iter().any(|item| matches!(item, ContentItem::ToolResult { .. }))
+}
+
+/// Answer to tool calls that were cancelled before they ran
+const CANCELLED_TOOL_RESULT: &str = "Cancelled by the user";
+
+/// Aborts the model call or tool the chat is running.  It is shared rather than behind
+/// the chat's lock, since whoever cancels would otherwise wait for the call to finish.
+#[derive(Clone, Default)]
+pub struct CancelHandle(Arc<Mutex<Option<AbortHandle>>>);
+
+impl CancelHandle {
+    /// Returns whether anything was running.
+    pub fn cancel(&self) -> bool {
+        match self.0.lock().unwrap().take() {
+            Some(handle) => {
+                handle.abort();
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Runs `future` until it completes or `cancel` is called.
+    async fn run<T>(&self, future: impl Future<Output = T>) -> Result<T, InferenceError> {
+        let (future, handle) = abortable(future);
+        *self.0.lock().unwrap() = Some(handle);
+        let result = future.await;
+        self.0.lock().unwrap().take();
+        result.map_err(|_| InferenceError::Cancelled)
+    }
+}
+
+/// A provider together with the config it was built from.
+struct Profile {
+    config: ProjectConfig,
+    inference: Box<dyn Inference>,
 }
 
 pub struct Chat {
     pub messages: Vec<Message>,
-    inference: InferenceProvider,
-    tokenizer: Tokenizer,
+    /// The configured provider followed by its fallbacks
+    profiles: Vec<Profile>,
+    tokens: ContextCounter,
+    /// Size of the tool definitions sent along with every request
+    tool_tokens: usize,
     max_tokens: usize,
+    tools: ToolRegistry,
+    config: ProjectConfig,
+    pub usage: SessionUsage,
+    /// Project the chat works on, the git repository of the working directory when unset
+    pub root: Option<PathBuf>,
+    cancel: CancelHandle,
+    approvals: Approvals,
 }
 
 impl Chat {
     pub async fn new() -> Self {
-        let tokenizer = Tokenizer::from_bytes(TOKENIZER_JSON).expect("Failed to load tokenizer.");
-        let config = ProjectConfig::load().unwrap_or_default();
-        
-        // Dynamically choose inference provider based on configuration
-        let inference = match config.provider.as_str() {
-            "anthropic" => InferenceProvider::Anthropic(AnthropicInference::new()),
-            "deepseek" => InferenceProvider::DeepSeek(DeepSeekInference::new()),
-            "bedrock" => {
-                let bedrock_inference = AWSBedrockInference::new(
-                    config.model.clone(),           // model_id
-                    0.2,                            // temperature 
-                    Some(config.max_output_tokens as i32), // max_tokens
-                ).await.expect("Failed to initialize Bedrock inference");
-                InferenceProvider::Bedrock(bedrock_inference)
-            },
-            _ => InferenceProvider::OpenAI(OpenAIInference::new()),
-        };
+        Self::from_config(ProjectConfig::load().unwrap_or_default()).await
+    }
 
-        Self {
+    pub async fn from_config(config: ProjectConfig) -> Self {
+        Self::with_providers(config, &ProviderRegistry::default()).await
+            .expect("OpenAI compatible providers are always registered")
+    }
+
+    /// Like `from_config`, but takes the providers from `registry` so backends other than
+    /// the built-in ones can be used.
+    pub async fn with_providers(config: ProjectConfig, registry: &ProviderRegistry) -> Result<Self, anyhow::Error> {
+        let mut profiles = Vec::new();
+        let fallbacks = config.fallback.iter().map(|fallback| config.with_profile(fallback));
+        for profile_config in std::iter::once(config.clone()).chain(fallbacks) {
+            let inference = registry.create(&profile_config).await
+                .ok_or_else(|| anyhow::anyhow!("Unknown provider {}", profile_config.provider))?;
+            profiles.push(Profile { inference, config: profile_config });
+        }
+
+        let tools = ToolRegistry::default();
+        let tokens = ContextCounter::new(tokens::counter_for(&config));
+        let tool_tokens = tokens.count_text(&serde_json::to_string(&tools.anthropic_tools()).unwrap_or_default());
+
+        Ok(Self {
             messages: Vec::new(),
-            inference,
-            tokenizer,
+            profiles,
+            tokens,
+            tool_tokens,
             max_tokens: config.max_context,
+            tools,
+            config,
+            usage: SessionUsage::default(),
+            root: None,
+            cancel: CancelHandle::default(),
+            approvals: Approvals::default(),
+        })
+    }
+
+    /// Drops the oldest messages until the system prompt, tool definitions, history and
+    /// `message` about to be sent fit in `limit` tokens.  Returns the estimated size of the
+    /// request.
+    fn trim_messages_to_token_limit(&mut self, system_message: &str, message: &Message, limit: usize) -> usize {
+        self.tokens.retain(&self.messages);
+        let tokens = &mut self.tokens;
+        let counts = self.messages.iter().map(|msg| tokens.count_message(msg)).collect::<Vec<_>>();
+        let mut total = self.tool_tokens
+            + self.tokens.count_text(system_message)
+            + self.tokens.count_message(message)
+            + counts.iter().sum::<usize>();
+
+        let mut dropped = 0;
+        while total > limit && dropped < counts.len() {
+            total -= counts[dropped];
+            dropped += 1;
+        }
+        // Providers reject a history that opens with an answer or with orphaned tool results
+        if dropped > 0 {
+            while dropped < counts.len() && !starts_turn(&self.messages[dropped]) {
+                total -= counts[dropped];
+                dropped += 1;
+            }
         }
+        self.messages.drain(..dropped);
+        total
     }
 
-    fn content_to_string(content: &[ContentItem]) -> String {
-        content.iter()
-            .map(|item| match item {
-                ContentItem::Text { text } => text.clone(),
-                ContentItem::ToolUse { name, input, .. } => format!("tool {} with input: {:?}", name, input),
-                ContentItem::ToolResult { content, .. } => format!("tool result: {}", content),
-            })
-            .collect::<Vec<String>>()
-            .join(" ")
+    /// Trims the history with the local token estimate, asking the provider for the exact
+    /// count when the estimate comes close to the limit.
+    async fn fit_context(&mut self, system_message: &str, message: &Message) {
+        let estimate = self.trim_messages_to_token_limit(system_message, message, self.max_tokens);
+        if estimate < self.max_tokens / 5 * 4 {
+            return;
+        }
+
+        let mut messages = self.messages.clone();
+        messages.push(message.clone());
+        match self.profiles[0].inference.count_tokens(messages, Some(system_message)).await {
+            Some(Ok(exact)) if exact as usize > self.max_tokens => {
+                // Scale the limit by how far off the estimate was and trim again
+                let limit = self.max_tokens * estimate / exact as usize;
+                self.trim_messages_to_token_limit(system_message, message, limit);
+            },
+            Some(Err(e)) => log::warn!("Could not count tokens, using the estimate: {}", e),
+            _ => {},
+        }
+    }
+
+    /// Handle for aborting the model call or tool execution in flight.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
     }
 
-    fn calculate_total_tokens(&self) -> usize {
-        self.messages.iter()
-            .map(|msg| {
-                // Combine role and content for complete message token count
-                let text = format!("{:?} {}", msg.role, Self::content_to_string(&msg.content));
-                let encoding = self.tokenizer.encode(text, false).unwrap();
-                encoding.len()
+    /// Commands waiting for the user to approve them, shared with whoever answers.
+    pub fn approvals(&self) -> Approvals {
+        self.approvals.clone()
+    }
+
+    /// Answers the tool calls of the last assistant message when the user moves on without
+    /// running them, e.g. after cancelling one, since providers reject unanswered tool calls.
+    fn answer_pending_tool_uses(&self, message: &mut Message) {
+        let Some(last) = self.messages.last().filter(|msg| msg.role == Role::Assistant) else {
+            return;
+        };
+        let results = last.content.iter()
+            .filter_map(|item| match item {
+                ContentItem::ToolUse { id, .. } => Some(ContentItem::ToolResult {
+                    tool_use_id: id.clone(),
+                    content: CANCELLED_TOOL_RESULT.to_string(),
+                }),
+                _ => None,
             })
-            .sum()
+            .collect::<Vec<_>>();
+        message.content.splice(0..0, results);
     }
 
-    fn trim_messages_to_token_limit(&mut self) {
-        while self.calculate_total_tokens() > self.max_tokens && !self.messages.is_empty() {
-            self.messages.remove(0);
+    fn project_root(&self) -> Result<PathBuf, anyhow::Error> {
+        match &self.root {
+            Some(root) => Ok(root.clone()),
+            None => GitTree::get_git_root(),
         }
     }
 
-    fn extract_string_field<'a>(
-        input: &'a serde_json::Value,
-        field_name: &str
-    ) -> Result<&'a str, anyhow::Error> {
-        input.get(field_name)
-            .ok_or_else(|| anyhow::anyhow!("Missing '{}' field in tool input: {:?}", field_name, input))?
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("'{}' field is not a string: {:?}", field_name, input.get(field_name)))
+    /// Replaces image paths with the image data so the history only holds content every
+    /// provider can send, and refuses images for text-only models.
+    fn load_images(&self, mut message: Message) -> Result<Message, anyhow::Error> {
+        if !message.content.iter().any(|item| matches!(item, ContentItem::Image { .. })) {
+            return Ok(message);
+        }
+        if !self.config.supports_images(&self.config.model) {
+            return Err(InferenceError::UnsupportedContent(format!("{} does not accept images", self.config.model)).into());
+        }
+
+        let ctx = self.tool_context()?;
+        for item in message.content.iter_mut() {
+            if let ContentItem::Image { source: ImageSource::Path { path } } = item {
+                let source = load_image(&ctx, path)?;
+                *item = ContentItem::Image { source };
+            }
+        }
+        Ok(message)
     }
 
     pub async fn send_message(&mut self, message: Message) -> Result<Message, anyhow::Error> {
+        self.send_message_with_events(message, None).await
+    }
+
+    /// Like `send_message`, but reports progress on `events` while the model is generating.
+    /// The final message is both returned and sent as `ChatEvent::Message`.
+    pub async fn send_message_streaming(&mut self, message: Message, events: &UnboundedSender<ChatEvent>) -> Result<Message, anyhow::Error> {
+        let new_msg = self.send_message_with_events(message, Some(events)).await?;
+        let _ = events.send(ChatEvent::Message { message: new_msg.clone() });
+        Ok(new_msg)
+    }
+
+    async fn send_message_with_events(&mut self, message: Message, events: Option<&UnboundedSender<ChatEvent>>) -> Result<Message, anyhow::Error> {
         if message.role == Role::User {
-            let tree_string = GitTree::get_tree()?;
+            let mut message = self.load_images(message)?;
+            let tree_string = GitTree::get_tree_of(&self.project_root()?)?;
             let system_message = format!(
                 r#"
                 You are a coding assistant working on a project.
@@ -130,27 +304,52 @@ impl Chat {
                 Always call 'compile_check' tool after completing changes that the user requests.  If compile_check shows any errors, make subsequent calls to correct the errors. Continue checking and rewriting until there are no more errors.  If there are warnings then do not try to fix them, just let the user know.  If any bash commands are needed like installing packages use tool 'execute'.
 
                 Never make any changes outside of the project's root directory.
-                Always read and write entire file contents.  Never write partial contents of a file.
+                Change existing files with 'edit_file', giving just enough of the surrounding code to make each change unique.  Only use 'write_file' for new files or when most of a file changes, and then always write the entire file contents.
 
                 The user may also general questions and in that case simply answer but do not execute any tools.
                 "#,
                 &tree_string,
             );
-            self.trim_messages_to_token_limit();
+            // Tool results continue the request that produced the tool calls, anything else
+            // is a new request from the user
+            let is_new_turn = starts_turn(&message);
+            if is_new_turn {
+                self.answer_pending_tool_uses(&mut message);
+            }
+            self.fit_context(&system_message, &message).await;
             self.messages.push(message);
-            
-            match self.inference.query_model(self.messages.clone(), Some(&system_message)).await {
-                Ok(response) => {
+
+            let response = self.cancel.run(self.query_with_fallback(&system_message, events)).await
+                .and_then(|response| response);
+            match response {
+                Ok((response, profile_model)) => {
+                    let model = if response.model.is_empty() { profile_model.clone() } else { response.model.clone() };
+                    let price = self.config.price_for(&model)
+                        .or_else(|| self.config.price_for(&profile_model));
+                    if is_new_turn {
+                        self.usage.start_turn(&model);
+                    }
+                    self.usage.record(&model, response.usage, price.as_ref());
+                    log::info!(
+                        "{} usage: {} input, {} cache write, {} cache read, {} output tokens",
+                        model,
+                        response.usage.input_tokens,
+                        response.usage.cache_creation_input_tokens,
+                        response.usage.cache_read_input_tokens,
+                        response.usage.output_tokens,
+                    );
+
                     let new_msg = Message {
                         role: Role::Assistant,
-                        content: response.content.clone()
+                        content: response.content.clone(),
+                        model: Some(model),
                     };
                     self.messages.push(new_msg.clone());
                     Ok(new_msg)
                 },
                 Err(e) => {
                     self.messages.pop();
-                    Err(e)
+                    Err(e.into())
                 }
             }
         } else {
@@ -158,70 +357,473 @@ impl Chat {
         }
     }
 
+    /// Queries the providers in order, moving on to the next one only when a provider is
+    /// unavailable (network errors, 5xx, rate limits) after its own retries ran out.  Returns
+    /// the response with the model of the provider that answered.  Text-only models are
+    /// skipped while the history holds images.
+    async fn query_with_fallback(&self, system_message: &str, events: Option<&UnboundedSender<ChatEvent>>) -> Result<(ModelResponse, String), InferenceError> {
+        let has_images = self.messages.iter()
+            .any(|message| message.content.iter().any(|item| matches!(item, ContentItem::Image { .. })));
+        let mut last_error: Option<InferenceError> = None;
+        for profile in &self.profiles {
+            if has_images && !profile.config.supports_images(&profile.config.model) {
+                log::warn!("Skipping {} {}, it does not accept images", profile.config.provider, profile.config.model);
+                continue;
+            }
+            if let Some(error) = last_error.take() {
+                log::warn!("Falling back to {} {}: {}", profile.config.provider, profile.config.model, error);
+                if let Some(events) = events {
+                    let _ = events.send(ChatEvent::Fallback { model: profile.config.model.clone(), error: error.to_string() });
+                }
+            }
+
+            let on_retry = |progress: &RetryProgress| {
+                if let Some(events) = events {
+                    let _ = events.send(ChatEvent::Retry(progress.clone()));
+                }
+            };
+            let response = with_retry(&self.config.retry, on_retry, || async {
+                let stream = profile.inference.query_model_stream(self.messages.clone(), Some(system_message)).await?;
+                collect_response(stream, events).await
+            }).await;
+
+            match response {
+                Ok(response) => return Ok((response, profile.config.model.clone())),
+                Err(e) if is_retryable(&e) => last_error = Some(e),
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| if has_images {
+            InferenceError::UnsupportedContent("None of the configured models accept images".to_string())
+        } else {
+            InferenceError::InvalidResponse("No provider configured".to_string())
+        }))
+    }
+
     pub async fn handle_tool_use(&mut self, content_item: &ContentItem) -> Result<String, anyhow::Error> {
-        match content_item {
-            ContentItem::ToolUse { name, input, .. } => {
-                match GitTree::get_git_root() {
-                    Ok(root_path) => {
-                        let tool_result = match name.as_str() {
-                            "write_file" => {
-                                let content = Self::extract_string_field(input, "content")?;
-                                let file_path = Self::extract_string_field(input, "path")?;
-                                let full_path = root_path.join(file_path);
-                                match std::fs::write(full_path.clone(), content) {
-                                    Ok(_) => format!("Successfully wrote content to file {:?}.", full_path),
-                                    Err(e) => format!("Error writing to file {:?}: {:?}.", full_path, e),
-                                }
-                            },
-                            "read_file" => {
-                                let file_path = Self::extract_string_field(input, "path")?;
-                                let full_path = root_path.join(file_path);
-                                match std::fs::read_to_string(full_path.clone()) {
-                                    Ok(file_content) => file_content,
-                                    Err(e) => format!("Error reading file {:?}: {:?}.", full_path, e),
-                                }
-                            },
-                            "compile_check" => {
-                                let check_cmd = Self::extract_string_field(input, "cmd")?;
-                                let output = Command::new("bash")
-                                    .arg("-c")
-                                    .arg(format!("{} & sleep 5; kill $!", check_cmd))
-                                    .current_dir(root_path)
-                                    .output()
-                                    .expect("Failed to execute command");
-
-                                let stdout = String::from_utf8_lossy(&output.stdout);
-                                let stderr = String::from_utf8_lossy(&output.stderr);
-                                format!("Stdout:
-{}
-Stderr:
-{}", stdout, stderr)
-                            },
-                            "execute" => {
-                                let statement = Self::extract_string_field(input, "statement")?;
-                                let output = Command::new("bash")
-                                    .arg("-c")
-                                    .arg(statement)
-                                    .current_dir(root_path)
-                                    .output()
-                                    .expect("Failed to execute command");
-
-                                let stdout = String::from_utf8_lossy(&output.stdout);
-                                let stderr = String::from_utf8_lossy(&output.stderr);
-                                format!("Stdout:
-{}
-Stderr:
-{}", stdout, stderr)
-                            },
-                            _ => format!("Unknown tool: {}", name)
-                        };
-
-                        Ok(tool_result)
-                    },
-                    Err(e) => Err(anyhow::anyhow!("Error getting git root: {}", e))
+        let ctx = self.tool_context()?;
+        self.cancel.run(self.execute_tool(content_item, &ctx)).await?
+    }
+
+    /// Runs every tool use of an assistant message and answers them in a single user
+    /// message, which is how providers expect parallel tool calls to be answered.  Runs of
+    /// read-only tools execute concurrently, any other tool waits for the ones before it and
+    /// runs on its own.  A failing tool is reported in its result so the others still count.
+    pub async fn handle_tool_uses(&mut self, tool_uses: &[ContentItem]) -> Result<Message, anyhow::Error> {
+        let ctx = self.tool_context()?;
+        let content = self.cancel.run(async {
+            let mut results = Vec::with_capacity(tool_uses.len());
+            for batch in tool_uses.chunk_by(|a, b| self.is_read_only(a) && self.is_read_only(b)) {
+                let outputs = future::join_all(batch.iter().map(|item| self.execute_tool(item, &ctx))).await;
+                for (item, output) in batch.iter().zip(outputs) {
+                    let ContentItem::ToolUse { id, name, .. } = item else {
+                        return Err(anyhow::anyhow!("Not a tool use content item"));
+                    };
+                    results.push(ContentItem::ToolResult {
+                        tool_use_id: id.clone(),
+                        content: output.unwrap_or_else(|e| match e.downcast_ref::<CommandBlocked>() {
+                            Some(blocked) => blocked.to_tool_result(),
+                            None => format!("Error running {}: {}", name, e),
+                        }),
+                    });
                 }
+            }
+            Ok(results)
+        }).await??;
+
+        Ok(Message {
+            role: Role::User,
+            content,
+            model: None,
+        })
+    }
+
+    fn tool_context(&self) -> Result<ToolContext, anyhow::Error> {
+        let root_path = self.project_root()
+            .map_err(|e| anyhow::anyhow!("Error getting git root: {}", e))?;
+        Ok(ToolContext {
+            allowed_dirs: self.config.sandbox.allowed_dirs(&root_path),
+            root_path,
+            commands: self.config.commands.clone(),
+            approvals: self.approvals.clone(),
+        })
+    }
+
+    fn is_read_only(&self, content_item: &ContentItem) -> bool {
+        match content_item {
+            ContentItem::ToolUse { name, .. } => self.tools.get(name).is_some_and(|tool| tool.is_read_only()),
+            _ => false,
+        }
+    }
+
+    async fn execute_tool(&self, content_item: &ContentItem, ctx: &ToolContext) -> Result<String, anyhow::Error> {
+        match content_item {
+            ContentItem::ToolUse { name, input, .. } => match self.tools.get(name) {
+                Some(tool) => tool.execute(input, ctx).await,
+                None => Ok(format!("Unknown tool: {}", name)),
             },
             _ => Err(anyhow::anyhow!("Not a tool use content item"))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_image_stays_in_project() -> Result<(), anyhow::Error> {
+        let project = tempfile::tempdir()?;
+        std::fs::create_dir(project.path().join("screens"))?;
+        std::fs::write(project.path().join("screens/broken.png"), [0x89, b'P', b'N', b'G'])?;
+        std::fs::write(project.path().join("notes.txt"), "not an image")?;
+
+        std::fs::create_dir(project.path().join(".git"))?;
+        std::fs::write(project.path().join(".git/logo.png"), [0x89, b'P', b'N', b'G'])?;
+        let mut ctx = ToolContext::new(project.path().to_path_buf());
+
+        let source = load_image(&ctx, "screens/broken.png")?;
+        assert_eq!(source.base64(), Some(("image/png", "iVBORw==")));
+        assert!(load_image(&ctx, "notes.txt").is_err());
+        assert!(load_image(&ctx, ".git/logo.png").is_err());
+
+        let outside = tempfile::NamedTempFile::with_suffix(".png")?;
+        std::fs::write(outside.path(), [0x89, b'P', b'N', b'G'])?;
+        let escape = format!("../{}", outside.path().file_name().unwrap().to_string_lossy());
+        assert!(outside.path().parent() == project.path().parent());
+        assert!(load_image(&ctx, &escape).is_err());
+        ctx.allowed_dirs = vec![outside.path().parent().unwrap().to_path_buf()];
+        assert!(load_image(&ctx, &escape).is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_trims_oldest_turns_to_fit_context() {
+        let config = ProjectConfig { provider: "openai".to_string(), model: "gpt-4o".to_string(), ..ProjectConfig::default() };
+        let mut chat = Chat::from_config(config).await;
+        let user = |text: &str| Message { role: Role::User, content: vec![ContentItem::Text { text: text.to_string() }], model: None };
+        let tool_use = |id: &str| Message {
+            role: Role::Assistant,
+            content: vec![ContentItem::ToolUse { id: id.to_string(), name: "read_file".to_string(), input: serde_json::json!({"path": "src/main.rs"}) }],
+            model: None,
+        };
+        let tool_result = |id: &str| Message {
+            role: Role::User,
+            content: vec![ContentItem::ToolResult { tool_use_id: id.to_string(), content: "fn main() {}\n".repeat(50) }],
+            model: None,
+        };
+        chat.messages = vec![
+            user("Read main.rs"), tool_use("call_1"), tool_result("call_1"),
+            user("Add a ping module"), tool_use("call_2"), tool_result("call_2"),
+        ];
+        let next = user("Now add tests");
+
+        let full = chat.trim_messages_to_token_limit("Be brief.", &next, usize::MAX);
+        assert_eq!(chat.messages.len(), 6);
+
+        // Dropping the first message alone would fit, but leave a dangling tool use
+        let first = chat.tokens.count_message(&chat.messages[0]);
+        let total = chat.trim_messages_to_token_limit("Be brief.", &next, full - first);
+        assert_eq!(chat.messages.len(), 3);
+        assert!(starts_turn(&chat.messages[0]));
+        assert!(total < full - first);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_aborts_model_call_and_keeps_history() -> Result<(), anyhow::Error> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        tokio::spawn(async move {
+            // Takes the request and never answers it
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+        });
+        let project = tempfile::tempdir()?;
+        std::process::Command::new("git").arg("init").arg("-q").current_dir(project.path()).status()?;
+
+        let config = ProjectConfig {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            base_url,
+            api_key: "test-key".to_string(),
+            ..ProjectConfig::default()
+        };
+        let mut chat = Chat::from_config(config).await;
+        chat.root = Some(project.path().to_path_buf());
+        let user = |text: &str| Message { role: Role::User, content: vec![ContentItem::Text { text: text.to_string() }], model: None };
+        chat.messages = vec![user("Read main.rs"), Message {
+            role: Role::Assistant,
+            content: vec![ContentItem::ToolUse { id: "call_1".to_string(), name: "read_file".to_string(), input: serde_json::json!({"path": "src/main.rs"}) }],
+            model: None,
+        }];
+
+        // The user cancelled the tool call and asks for something else
+        let mut next = user("Add a ping module instead");
+        chat.answer_pending_tool_uses(&mut next);
+        assert!(matches!(&next.content[0], ContentItem::ToolResult { tool_use_id, content } if tool_use_id == "call_1" && content == CANCELLED_TOOL_RESULT));
+        assert!(matches!(&next.content[1], ContentItem::Text { .. }));
+
+        let cancel = chat.cancel_handle();
+        assert!(!cancel.cancel());
+        tokio::spawn(async move {
+            while !cancel.cancel() {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        });
+        let error = chat.send_message(user("Add a ping module instead")).await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<InferenceError>(), Some(InferenceError::Cancelled)));
+        assert_eq!(chat.messages.len(), 2);
+        assert!(!chat.cancel_handle().cancel());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tool_uses_are_answered_together() -> Result<(), anyhow::Error> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use async_trait::async_trait;
+        use crate::inference::tools::InputSchema;
+        use crate::tools::Tool;
+
+        /// Tracks how many tools run at the same time
+        #[derive(Clone, Default)]
+        struct Probe {
+            running: Arc<AtomicUsize>,
+            most_running: Arc<AtomicUsize>,
+        }
+
+        struct ProbeTool {
+            name: &'static str,
+            read_only: bool,
+            probe: Probe,
+        }
+
+        #[async_trait]
+        impl Tool for ProbeTool {
+            fn name(&self) -> &'static str {
+                self.name
+            }
+
+            fn description(&self) -> &'static str {
+                "Waits a bit"
+            }
+
+            fn input_schema(&self) -> InputSchema {
+                InputSchema::object(&[("path", "string", "A path")], &["path"])
+            }
+
+            fn is_read_only(&self) -> bool {
+                self.read_only
+            }
+
+            async fn execute(&self, input: &serde_json::Value, _ctx: &ToolContext) -> Result<String, anyhow::Error> {
+                let running = self.probe.running.fetch_add(1, Ordering::SeqCst) + 1;
+                self.probe.most_running.fetch_max(running, Ordering::SeqCst);
+                if !self.read_only {
+                    assert_eq!(running, 1, "{} ran alongside another tool", self.name);
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                self.probe.running.fetch_sub(1, Ordering::SeqCst);
+                match input["path"].as_str() {
+                    Some("missing.rs") => Err(anyhow::anyhow!("missing.rs does not exist")),
+                    path => Ok(format!("{} {}", self.name, path.unwrap_or_default())),
+                }
+            }
+        }
+
+        let mut chat = Chat::from_config(ProjectConfig::default()).await;
+        chat.root = Some(std::env::temp_dir());
+        let probe = Probe::default();
+        chat.tools.register(ProbeTool { name: "peek", read_only: true, probe: probe.clone() });
+        chat.tools.register(ProbeTool { name: "poke", read_only: false, probe: probe.clone() });
+        let tool_use = |id: &str, name: &str, path: &str| ContentItem::ToolUse {
+            id: id.to_string(),
+            name: name.to_string(),
+            input: serde_json::json!({"path": path}),
+        };
+
+        let message = chat.handle_tool_uses(&[
+            tool_use("call_1", "peek", "a.rs"),
+            tool_use("call_2", "peek", "missing.rs"),
+            tool_use("call_3", "peek", "c.rs"),
+            tool_use("call_4", "poke", "a.rs"),
+            tool_use("call_5", "peek", "a.rs"),
+        ]).await?;
+
+        assert_eq!(message.role, Role::User);
+        let results = message.content.iter()
+            .map(|item| match item {
+                ContentItem::ToolResult { tool_use_id, content } => (tool_use_id.as_str(), content.as_str()),
+                _ => panic!("not a tool result: {:?}", item),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(results, [
+            ("call_1", "peek a.rs"),
+            ("call_2", "Error running peek: missing.rs does not exist"),
+            ("call_3", "peek c.rs"),
+            ("call_4", "poke a.rs"),
+            ("call_5", "peek a.rs"),
+        ]);
+        assert_eq!(probe.most_running.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_when_provider_is_unavailable() -> Result<(), InferenceError> {
+        use crate::config::{ProviderProfile, RetryConfig, SamplingConfig};
+        use crate::inference::stub_server::{self, StubResponse};
+
+        let (primary_url, _) = stub_server::spawn(vec![StubResponse {
+            status: 503,
+            content_type: "application/json",
+            body: r#"{"error": {"message": "overloaded"}}"#.to_string(),
+        }]).await;
+        let (fallback_url, fallback_requests) = stub_server::spawn(vec![StubResponse::json(
+            include_str!("../tests/fixtures/openai/tool_call_response.json")
+        )]).await;
+
+        let config = ProjectConfig {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            base_url: primary_url,
+            api_key: "primary-key".to_string(),
+            retry: RetryConfig { max_attempts: 1, ..RetryConfig::default() },
+            ..ProjectConfig::default()
+        };
+        let config = ProjectConfig {
+            fallback: vec![ProviderProfile {
+                provider: "openai".to_string(),
+                model: "gpt-4o-mini".to_string(),
+                base_url: fallback_url,
+                api_key: "fallback-key".to_string(),
+                max_output_tokens: None,
+                sampling: SamplingConfig::default(),
+            }],
+            ..config
+        };
+        let mut chat = Chat::from_config(config).await;
+        chat.messages.push(Message {
+            role: Role::User,
+            content: vec![ContentItem::Text { text: "Add a ping module".to_string() }],
+            model: None,
+        });
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let (response, model) = chat.query_with_fallback("Be brief.", Some(&tx)).await?;
+
+        assert_eq!(model, "gpt-4o-mini");
+        assert_eq!(response.stop_reason, "tool_calls");
+        assert!(matches!(rx.try_recv(), Ok(ChatEvent::Fallback { model, .. }) if model == "gpt-4o-mini"));
+        let request = fallback_requests.lock().unwrap()[0].clone();
+        assert_eq!(request.header("authorization"), Some("Bearer fallback-key"));
+        assert_eq!(request.json()["model"], "gpt-4o-mini");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fallback_uses_its_own_sampling() -> Result<(), InferenceError> {
+        use crate::config::{ProviderProfile, RetryConfig, SamplingConfig};
+        use crate::inference::stub_server::{self, StubResponse};
+
+        let (primary_url, _) = stub_server::spawn(vec![StubResponse {
+            status: 503,
+            content_type: "application/json",
+            body: r#"{"error": {"message": "overloaded"}}"#.to_string(),
+        }]).await;
+        let stream = [
+            r#"{"type":"message_start","message":{"id":"msg_01","role":"assistant","model":"claude-3-5-haiku-20241022","usage":{"input_tokens":12,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":"Done."}}"#,
+            r#"{"type":"content_block_stop","index":0}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":3}}"#,
+            r#"{"type":"message_stop"}"#,
+        ].iter().map(|data| format!("data: {}\n\n", data)).collect::<String>();
+        let (fallback_url, fallback_requests) = stub_server::spawn(vec![StubResponse {
+            status: 200,
+            content_type: "text/event-stream",
+            body: stream,
+        }]).await;
+
+        // Anthropic has no seed, which must not keep it from taking over
+        let config = ProjectConfig {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            base_url: primary_url,
+            api_key: "primary-key".to_string(),
+            retry: RetryConfig { max_attempts: 1, ..RetryConfig::default() },
+            sampling: SamplingConfig { seed: Some(42), temperature: Some(0.2), ..SamplingConfig::default() },
+            fallback: vec![ProviderProfile {
+                provider: "anthropic".to_string(),
+                model: "claude-3-5-haiku-20241022".to_string(),
+                base_url: fallback_url,
+                api_key: "fallback-key".to_string(),
+                max_output_tokens: None,
+                sampling: SamplingConfig { top_k: Some(40), ..SamplingConfig::default() },
+            }],
+            ..ProjectConfig::default()
+        };
+        let mut chat = Chat::from_config(config).await;
+        chat.messages.push(Message {
+            role: Role::User,
+            content: vec![ContentItem::Text { text: "Add a ping module".to_string() }],
+            model: None,
+        });
+
+        let (_, model) = chat.query_with_fallback("Be brief.", None).await?;
+
+        assert_eq!(model, "claude-3-5-haiku-20241022");
+        let body = fallback_requests.lock().unwrap()[0].json();
+        assert_eq!(body["top_k"], 40);
+        assert!(body.get("temperature").is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fallback_skips_text_only_models_for_images() -> Result<(), InferenceError> {
+        use crate::config::{ProviderProfile, RetryConfig, SamplingConfig};
+        use crate::inference::stub_server::{self, StubResponse};
+
+        let unavailable = || StubResponse {
+            status: 503,
+            content_type: "application/json",
+            body: r#"{"error": {"message": "overloaded"}}"#.to_string(),
+        };
+        let (primary_url, _) = stub_server::spawn(vec![unavailable()]).await;
+        let (text_only_url, text_only_requests) = stub_server::spawn(vec![unavailable()]).await;
+        let (vision_url, vision_requests) = stub_server::spawn(vec![StubResponse::json(
+            include_str!("../tests/fixtures/openai/tool_call_response.json")
+        )]).await;
+
+        let profile = |model: &str, base_url: String| ProviderProfile {
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            base_url,
+            api_key: "key".to_string(),
+            max_output_tokens: None,
+            sampling: SamplingConfig::default(),
+        };
+        let config = ProjectConfig {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            base_url: primary_url,
+            api_key: "key".to_string(),
+            retry: RetryConfig { max_attempts: 1, ..RetryConfig::default() },
+            fallback: vec![profile("deepseek-chat", text_only_url), profile("gpt-4o-mini", vision_url)],
+            ..ProjectConfig::default()
+        };
+        let mut chat = Chat::from_config(config).await;
+        chat.messages.push(Message {
+            role: Role::User,
+            content: vec![
+                ContentItem::Text { text: "Why is this button broken?".to_string() },
+                ContentItem::Image { source: ImageSource::Base64 { media_type: "image/png".to_string(), data: "iVBORw0KGgo=".to_string() } },
+            ],
+            model: None,
+        });
+
+        let (_, model) = chat.query_with_fallback("Be brief.", None).await?;
+
+        assert_eq!(model, "gpt-4o-mini");
+        assert!(text_only_requests.lock().unwrap().is_empty());
+        assert_eq!(vision_requests.lock().unwrap().len(), 1);
+        Ok(())
+    }
+}
@@ -1,11 +1,15 @@
 use log::info;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use crate::inference::types::InferenceError;
 use crate::tree::GitTree;
+use crate::usage::{default_pricing, ModelPrice};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectConfig {
     pub model: String,
     pub check_cmd: String,
@@ -19,6 +23,367 @@ pub struct ProjectConfig {
     pub max_output_tokens: u32,
     #[serde(default)]
     pub provider: String,
+    /// Token budget for extended thinking on Anthropic models, thinking is off when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking_budget: Option<u32>,
+    /// Models that can't take images, in addition to the built-in ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub text_only_models: Vec<String>,
+    /// Path to a Hugging Face `tokenizer.json` to count context tokens with, e.g. DeepSeek's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer: Option<String>,
+    #[serde(default, skip_serializing_if = "SamplingConfig::is_empty")]
+    pub sampling: SamplingConfig,
+    /// Per-model prices in USD per million tokens, keyed by model name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub pricing: HashMap<String, ModelPrice>,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    #[serde(default)]
+    pub timeouts: TimeoutConfig,
+    #[serde(default, skip_serializing_if = "OpenAIConfig::is_empty")]
+    pub openai: OpenAIConfig,
+    #[serde(default, skip_serializing_if = "AzureConfig::is_empty")]
+    pub azure: AzureConfig,
+    #[serde(default, skip_serializing_if = "OllamaConfig::is_empty")]
+    pub ollama: OllamaConfig,
+    #[serde(default, skip_serializing_if = "BedrockConfig::is_empty")]
+    pub bedrock: BedrockConfig,
+    #[serde(default, skip_serializing_if = "ReplayConfig::is_empty")]
+    pub replay: ReplayConfig,
+    #[serde(default, skip_serializing_if = "SandboxConfig::is_empty")]
+    pub sandbox: SandboxConfig,
+    #[serde(default, skip_serializing_if = "CommandPolicy::is_empty")]
+    pub commands: CommandPolicy,
+    /// Providers to fail over to, in order, when the configured one keeps failing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback: Vec<ProviderProfile>,
+}
+
+/// A provider and model to use instead of the top level ones.  Everything else, like
+/// pricing and retries, is shared with the top level config, except for sampling: the
+/// top level `[sampling]` may set parameters this provider doesn't have.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderProfile {
+    pub provider: String,
+    pub model: String,
+    #[serde(default)]
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: String,
+    pub max_output_tokens: Option<u32>,
+    /// Sampling for this provider, the provider's defaults when unset
+    #[serde(default, skip_serializing_if = "SamplingConfig::is_empty")]
+    pub sampling: SamplingConfig,
+}
+
+/// How the model picks its output.  Unset parameters are left to the provider, setting
+/// one the provider doesn't support fails the request.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SamplingConfig {
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub top_k: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    pub seed: Option<u64>,
+    pub tool_choice: Option<ToolChoice>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolChoice {
+    /// The model decides whether to call a tool
+    Auto,
+    /// The model has to call one of the tools
+    Any,
+    /// The model can't call tools
+    None,
+    /// The model has to call the named tool
+    Tool(String),
+}
+
+impl SamplingConfig {
+    fn is_empty(&self) -> bool {
+        *self == SamplingConfig::default()
+    }
+
+    /// Names of the parameters that are set.
+    fn set_parameters(&self) -> Vec<&'static str> {
+        [
+            ("temperature", self.temperature.is_some()),
+            ("top_p", self.top_p.is_some()),
+            ("top_k", self.top_k.is_some()),
+            ("stop_sequences", !self.stop_sequences.is_empty()),
+            ("seed", self.seed.is_some()),
+            ("tool_choice", self.tool_choice.is_some()),
+        ].into_iter()
+            .filter_map(|(name, set)| set.then_some(name))
+            .collect()
+    }
+
+    /// Fails when a parameter is out of range or not in the `supported` parameters of
+    /// `provider`.
+    pub fn validate(&self, provider: &str, supported: &[&str]) -> Result<(), InferenceError> {
+        let unsupported = self.set_parameters().into_iter()
+            .filter(|name| !supported.contains(name))
+            .collect::<Vec<_>>();
+        if !unsupported.is_empty() {
+            return Err(InferenceError::InvalidConfig(format!(
+                "{} does not support {} in [sampling]", provider, unsupported.join(", ")
+            )));
+        }
+        if self.temperature.is_some_and(|t| !(0.0..=2.0).contains(&t)) {
+            return Err(InferenceError::InvalidConfig("temperature must be between 0 and 2".to_string()));
+        }
+        if self.top_p.is_some_and(|p| p <= 0.0 || p > 1.0) {
+            return Err(InferenceError::InvalidConfig("top_p must be above 0 and at most 1".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// How the API key is sent to an OpenAI compatible endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthScheme {
+    /// `Authorization: Bearer <key>`
+    Bearer,
+    /// `api-key: <key>`, as Azure expects
+    ApiKey,
+    /// No key, e.g. behind a gateway that adds its own credentials
+    None,
+}
+
+/// Request details for OpenAI compatible endpoints reached through a gateway or proxy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenAIConfig {
+    /// Bearer, or api-key for Azure, when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_scheme: Option<AuthScheme>,
+    /// Added to the URL of every request
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub query_params: BTreeMap<String, String>,
+    /// Sent with every request, after the auth header so they can replace it
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra_headers: BTreeMap<String, String>,
+}
+
+impl OpenAIConfig {
+    fn is_empty(&self) -> bool {
+        *self == OpenAIConfig::default()
+    }
+}
+
+/// Azure OpenAI, used with `provider = "azure"` and the resource endpoint as `base_url`.
+/// The `[openai]` table applies on top.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AzureConfig {
+    /// Deployment serving the model, the model name when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deployment: Option<String>,
+    /// `api-version` query parameter, a recent GA version when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
+}
+
+impl AzureConfig {
+    fn is_empty(&self) -> bool {
+        *self == AzureConfig::default()
+    }
+}
+
+/// Model options only understood by Ollama's native API.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OllamaConfig {
+    /// Context window to load the model with, Ollama's own default is small
+    pub num_ctx: Option<u32>,
+}
+
+impl OllamaConfig {
+    fn is_empty(&self) -> bool {
+        *self == OllamaConfig::default()
+    }
+}
+
+/// AWS settings for Bedrock, anything unset comes from the usual AWS environment
+/// variables and shared config files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BedrockConfig {
+    pub region: Option<String>,
+    /// Named profile from `~/.aws/config`
+    pub profile: Option<String>,
+    /// Replaces the Bedrock runtime endpoint, e.g. for a VPC endpoint or a local mock
+    pub endpoint_url: Option<String>,
+}
+
+impl BedrockConfig {
+    fn is_empty(&self) -> bool {
+        *self == BedrockConfig::default()
+    }
+}
+
+/// Where file tools may go besides the project.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SandboxConfig {
+    /// Extra directories, absolute, relative to the project root or starting with `~/`
+    #[serde(default)]
+    pub allowed_dirs: Vec<String>,
+}
+
+impl SandboxConfig {
+    fn is_empty(&self) -> bool {
+        *self == SandboxConfig::default()
+    }
+
+    /// The allowed directories as paths, relative ones are taken from `root`.
+    pub fn allowed_dirs(&self, root: &Path) -> Vec<PathBuf> {
+        self.allowed_dirs.iter()
+            .map(|dir| match dir.strip_prefix("~/") {
+                Some(in_home) => home::home_dir().unwrap_or_default().join(in_home),
+                None => root.join(dir),
+            })
+            .collect()
+    }
+}
+
+/// What happens to a command the model wants to run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandAction {
+    #[default]
+    Allow,
+    /// Wait for the user to approve it through `/approve`
+    Ask,
+    Deny,
+}
+
+/// Rules for the commands of the `execute` and `compile_check` tools.  A rule is the start
+/// of a command where `*` matches anything, e.g. `cargo *` or `git push`.  Every part of a
+/// compound command (`a && b`, `a | b`, ...) is checked, deny rules win over ask rules and
+/// ask rules over allow rules.  Commands no rule matches get `default`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandPolicy {
+    pub default: CommandAction,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ask: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+    /// How long a command waits for approval before it counts as declined
+    pub approval_timeout_secs: u64,
+}
+
+impl Default for CommandPolicy {
+    fn default() -> Self {
+        CommandPolicy {
+            default: CommandAction::Allow,
+            allow: Vec::new(),
+            ask: Vec::new(),
+            deny: Vec::new(),
+            approval_timeout_secs: 300,
+        }
+    }
+}
+
+impl CommandPolicy {
+    fn is_empty(&self) -> bool {
+        *self == CommandPolicy::default()
+    }
+
+    pub fn approval_timeout(&self) -> Duration {
+        Duration::from_secs(self.approval_timeout_secs)
+    }
+}
+
+/// Recorded model calls for running offline, used with `provider = "replay"`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplayConfig {
+    /// JSON file holding the recorded requests and responses
+    pub cassette: String,
+    #[serde(default)]
+    pub mode: ReplayMode,
+    /// Provider that answers while recording
+    #[serde(default)]
+    pub provider: String,
+}
+
+impl ReplayConfig {
+    fn is_empty(&self) -> bool {
+        *self == ReplayConfig::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayMode {
+    /// Answer from the cassette, requests that weren't recorded fail
+    #[default]
+    Replay,
+    /// Query the real provider and write every request and response to the cassette
+    Record,
+}
+
+/// Retries for rate limits, overloads and transient network or server errors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Give up once retrying would take longer than this since the first attempt
+    pub deadline_secs: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: 5,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 60_000,
+            deadline_secs: 300,
+        }
+    }
+}
+
+/// Limits on how long a single model call may take.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    pub connect_secs: u64,
+    /// Longest wait for the next bytes of a response
+    pub read_secs: u64,
+    /// Longest a whole model call may take, including a streamed response
+    pub request_secs: u64,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        TimeoutConfig {
+            connect_secs: 10,
+            read_secs: 120,
+            request_secs: 600,
+        }
+    }
+}
+
+impl TimeoutConfig {
+    pub fn connect(&self) -> Duration {
+        Duration::from_secs(self.connect_secs)
+    }
+
+    pub fn read(&self) -> Duration {
+        Duration::from_secs(self.read_secs)
+    }
+
+    pub fn request(&self) -> Duration {
+        Duration::from_secs(self.request_secs)
+    }
 }
 
 impl Default for ProjectConfig {
@@ -31,12 +396,52 @@ impl Default for ProjectConfig {
             max_context: 100000,
             max_output_tokens: 8096,
             provider: String::from("anthropic"),
+            thinking_budget: None,
+            text_only_models: Vec::new(),
+            tokenizer: None,
+            sampling: SamplingConfig::default(),
+            pricing: HashMap::new(),
+            retry: RetryConfig::default(),
+            timeouts: TimeoutConfig::default(),
+            openai: OpenAIConfig::default(),
+            azure: AzureConfig::default(),
+            ollama: OllamaConfig::default(),
+            bedrock: BedrockConfig::default(),
+            replay: ReplayConfig::default(),
+            sandbox: SandboxConfig::default(),
+            commands: CommandPolicy::default(),
+            fallback: Vec::new(),
         }
     }
 }
 
 impl ProjectConfig {
     const CONFIG_FILE: &'static str = "pprog.toml";
+    const TEXT_ONLY_MODELS: [&'static str; 5] = ["deepseek-chat", "deepseek-reasoner", "o1-mini", "o3-mini", "gpt-3.5-turbo"];
+
+    pub fn supports_images(&self, model: &str) -> bool {
+        !Self::TEXT_ONLY_MODELS.contains(&model) && !self.text_only_models.iter().any(|m| m == model)
+    }
+
+    /// This config with the provider, model and endpoint of `profile`.
+    pub fn with_profile(&self, profile: &ProviderProfile) -> ProjectConfig {
+        ProjectConfig {
+            provider: profile.provider.clone(),
+            model: profile.model.clone(),
+            base_url: profile.base_url.clone(),
+            api_key: profile.api_key.clone(),
+            max_output_tokens: profile.max_output_tokens.unwrap_or(self.max_output_tokens),
+            sampling: profile.sampling.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// Looks up the price of `model`, preferring the `[pricing]` table over built-in prices.
+    pub fn price_for(&self, model: &str) -> Option<ModelPrice> {
+        self.pricing.get(model)
+            .copied()
+            .or_else(|| default_pricing().get(model).copied())
+    }
 
     fn detect_check_cmd() -> String {
         let root_path = match GitTree::get_git_root() {
@@ -137,6 +542,21 @@ impl ProjectConfig {
             max_context: 100000,
             max_output_tokens: 8096,
             provider: String::from("anthropic"),
+            thinking_budget: None,
+            text_only_models: Vec::new(),
+            tokenizer: None,
+            sampling: SamplingConfig::default(),
+            pricing: HashMap::new(),
+            retry: RetryConfig::default(),
+            timeouts: TimeoutConfig::default(),
+            openai: OpenAIConfig::default(),
+            azure: AzureConfig::default(),
+            ollama: OllamaConfig::default(),
+            bedrock: BedrockConfig::default(),
+            replay: ReplayConfig::default(),
+            sandbox: SandboxConfig::default(),
+            commands: CommandPolicy::default(),
+            fallback: Vec::new(),
         };
         config.save()?;
 
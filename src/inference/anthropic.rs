@@ -1,21 +1,90 @@
-use std::collections::HashMap;
-use reqwest::Client;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use reqwest::{Client, StatusCode};
 use serde::{Serialize, Deserialize};
+use serde_json::json;
 use anyhow::Result;
 
-use crate::config::ProjectConfig;
+use crate::config::{ProjectConfig, SamplingConfig, ToolChoice};
+use super::http;
 use super::types::{
-    ContentItem, InferenceError, Message, ModelResponse
+    ContentItem, EventStream, Inference, InferenceError, Message, ModelResponse, StreamEvent, Usage
 };
-use super::tools::{AnthropicTool, InputSchema, PropertySchema};
+use super::sse::{SseEvent, SseParser};
+use crate::tools::ToolRegistry;
+use super::tools::AnthropicTool;
 
 #[derive(Serialize)]
 struct AnthropicRequest<'a> {
     model: &'a str,
-    messages: Vec<Message>,
+    messages: Vec<serde_json::Value>,
     max_tokens: u32,
     tools: serde_json::Value,
-    system: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    system: Vec<SystemBlock>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ThinkingConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop_sequences: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+/// Body of `/messages/count_tokens`, a message request without the output settings.
+#[derive(Serialize)]
+struct CountTokensRequest<'a> {
+    model: &'a str,
+    messages: Vec<serde_json::Value>,
+    tools: serde_json::Value,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    system: Vec<SystemBlock>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ThinkingConfig>,
+}
+
+#[derive(Deserialize)]
+struct CountTokensResponse {
+    input_tokens: u64,
+}
+
+#[derive(Serialize)]
+struct ThinkingConfig {
+    #[serde(rename = "type")]
+    thinking_type: &'static str,
+    budget_tokens: u32,
+}
+
+#[derive(Serialize)]
+struct SystemBlock {
+    #[serde(rename = "type")]
+    block_type: &'static str,
+    text: String,
+    cache_control: CacheControl,
+}
+
+/// Prompt caching breakpoint, everything in the request up to and including the block
+/// carrying it is cached.
+#[derive(Serialize)]
+struct CacheControl {
+    #[serde(rename = "type")]
+    cache_type: &'static str,
+}
+
+impl CacheControl {
+    fn ephemeral() -> Self {
+        CacheControl { cache_type: "ephemeral" }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,184 +95,175 @@ struct AnthropicResponse {
     content: Vec<ContentItem>,
     stop_reason: String,
     stop_sequence: Option<String>,
+    usage: Usage,
 }
 
 pub struct AnthropicInference {
     model: String,
     client: Client,
+    read_timeout: Duration,
     base_url: String,
     api_key: String,
     max_output_tokens: u32,
+    thinking_budget: Option<u32>,
+    sampling: SamplingConfig,
+    tools: ToolRegistry,
 }
 
 impl std::default::Default for AnthropicInference {
     fn default() -> Self {
-        let config = match ProjectConfig::load() {
-            Ok(config) => config,
-            Err(_) => ProjectConfig::default(),
-        };
-        
-        AnthropicInference {
-            model: config.model,
-            client: Client::new(),
-            base_url: config.base_url,
-            api_key: config.api_key,
-            max_output_tokens: config.max_output_tokens,
-        }
+        Self::from_config(&ProjectConfig::load().unwrap_or_default())
     }
 }
 
 impl AnthropicInference {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn from_config(config: &ProjectConfig) -> Self {
+        AnthropicInference {
+            model: config.model.clone(),
+            client: http::client(&config.timeouts),
+            read_timeout: config.timeouts.read(),
+            base_url: config.base_url.clone(),
+            api_key: config.api_key.clone(),
+            max_output_tokens: config.max_output_tokens,
+            thinking_budget: config.thinking_budget,
+            sampling: config.sampling.clone(),
+            tools: ToolRegistry::default(),
+        }
     }
 
     fn get_tools(&self) -> Vec<AnthropicTool> {
-        vec![
-            self.read_file_tool(),
-            self.write_file_tool(),
-            self.execute_tool(),
-            self.compile_check_tool(),
-        ]
+        self.tools.anthropic_tools()
     }
 
-    fn read_file_tool(&self) -> AnthropicTool {
-        AnthropicTool {
-            name: "read_file".to_string(),
-            description: "Read file as string using path relative to root directory of project.".to_string(),
-            input_schema: InputSchema {
-                schema_type: "object".to_string(),
-                properties: {
-                    let mut map = HashMap::new();
-                    map.insert(
-                        "path".to_string(),
-                        PropertySchema {
-                            property_type: "string".to_string(),
-                            description: "The file path relative to the project root directory".to_string(),
-                        },
-                    );
-                    map
-                },
-                required: vec!["path".to_string()],
-            },
-        }
+    fn get_tools_json(&self) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::to_value(self.get_tools())
     }
 
-    fn write_file_tool(&self) -> AnthropicTool {
-        AnthropicTool {
-            name: "write_file".to_string(),
-            description: "Write string to file at path relative to root directory of project.".to_string(),
-            input_schema: InputSchema {
-                schema_type: "object".to_string(),
-                properties: {
-                    let mut map = HashMap::new();
-                    map.insert(
-                        "path".to_string(),
-                        PropertySchema {
-                            property_type: "string".to_string(),
-                            description: "The file path relative to the project root directory".to_string(),
-                        },
-                    );
-                    map.insert(
-                        "content".to_string(),
-                        PropertySchema {
-                            property_type: "string".to_string(),
-                            description: "The content to write to the file".to_string(),
-                        },
-                    );
-                    map
-                },
-                required: vec!["path".to_string(), "content".to_string()],
-            },
+    /// Checks `[sampling]` against what the API accepts, which is less with thinking on.
+    fn validate_sampling(&self) -> Result<(), InferenceError> {
+        self.sampling.validate("anthropic", &["temperature", "top_p", "top_k", "stop_sequences", "tool_choice"])?;
+        if self.sampling.temperature.is_some_and(|t| t > 1.0) {
+            return Err(InferenceError::InvalidConfig("temperature must be between 0 and 1 for anthropic".to_string()));
         }
-    }
-
-    fn execute_tool(&self) -> AnthropicTool {
-        AnthropicTool {
-            name: "execute".to_string(),
-            description: "Execute bash statements as a single string..".to_string(),
-            input_schema: InputSchema {
-                schema_type: "object".to_string(),
-                properties: {
-                    let mut map = HashMap::new();
-                    map.insert(
-                        "statement".to_string(),
-                        PropertySchema {
-                            property_type: "string".to_string(),
-                            description: "The bash statement to be executed.".to_string(),
-                        },
-                    );
-                    map
-                },
-                required: vec!["statement".to_string()],
-            },
+        if self.thinking_budget.is_some() {
+            if self.sampling.temperature.is_some() || self.sampling.top_k.is_some() {
+                return Err(InferenceError::InvalidConfig("temperature and top_k can't be set with thinking_budget".to_string()));
+            }
+            if matches!(self.sampling.tool_choice, Some(ToolChoice::Any | ToolChoice::Tool(_))) {
+                return Err(InferenceError::InvalidConfig("thinking only allows tool_choice auto or none".to_string()));
+            }
         }
+        Ok(())
     }
 
-    fn compile_check_tool(&self) -> AnthropicTool {
-        AnthropicTool {
-            name: "compile_check".to_string(),
-            description: "Check if project compiles or runs without error.".to_string(),
-            input_schema: InputSchema {
-                schema_type: "object".to_string(),
-                properties: {
-                    let mut map = HashMap::new();
-                    map.insert(
-                        "cmd".to_string(),
-                        PropertySchema {
-                            property_type: "string".to_string(),
-                            description: "The command to check for compiler/interpreter errors.".to_string(),
-                        },
-                    );
-                    map
-                },
-                required: vec!["cmd".to_string()],
-            },
-        }
-    }
-
-    fn get_tools_json(&self) -> Result<serde_json::Value, serde_json::Error> {
-        serde_json::to_value(self.get_tools())
-    }
-
-    pub async fn query_model(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, InferenceError> {
+    fn build_request(&self, messages: Vec<Message>, system_message: Option<&str>, stream: bool) -> Result<AnthropicRequest<'_>, InferenceError> {
         if self.api_key.is_empty() {
             return Err(InferenceError::MissingApiKey("Anthropic API key not found".to_string()));
         }
+        self.validate_sampling()?;
 
-        let system = system_message.unwrap_or("").to_string();
+        // The system prompt carries the whole file tree and rarely changes between turns,
+        // so it is cached together with the tool list that precedes it
+        let system = system_message
+            .filter(|sys_msg| !sys_msg.is_empty())
+            .map(|sys_msg| vec![SystemBlock {
+                block_type: "text",
+                text: sys_msg.to_string(),
+                cache_control: CacheControl::ephemeral(),
+            }])
+            .unwrap_or_default();
 
-        let tools = self.get_tools_json()
+        let mut tools = self.get_tools_json()
+            .map_err(|e| InferenceError::SerializationError(e.to_string()))?;
+        let mut messages = messages.into_iter()
+            .map(|mut message| {
+                message.model = None;
+                message.content.retain(|item| !item.is_unsigned_thinking());
+                serde_json::to_value(message)
+            })
+            .collect::<Result<Vec<_>, _>>()
             .map_err(|e| InferenceError::SerializationError(e.to_string()))?;
+        add_cache_breakpoints(&mut tools, &mut messages);
 
-        let request = AnthropicRequest {
+        // Thinking tokens count towards max_tokens, the budget comes on top of the output
+        let thinking = self.thinking_budget.map(|budget_tokens| ThinkingConfig {
+            thinking_type: "enabled",
+            budget_tokens,
+        });
+        let max_tokens = self.max_output_tokens + self.thinking_budget.unwrap_or(0);
+
+        Ok(AnthropicRequest {
             model: &self.model,
             messages,
-            max_tokens: self.max_output_tokens,
+            max_tokens,
             tools,
             system,
+            thinking,
+            temperature: self.sampling.temperature,
+            top_p: self.sampling.top_p,
+            top_k: self.sampling.top_k,
+            stop_sequences: self.sampling.stop_sequences.clone(),
+            tool_choice: self.sampling.tool_choice.as_ref().map(|choice| match choice {
+                ToolChoice::Auto => json!({"type": "auto"}),
+                ToolChoice::Any => json!({"type": "any"}),
+                ToolChoice::None => json!({"type": "none"}),
+                ToolChoice::Tool(name) => json!({"type": "tool", "name": name}),
+            }),
+            stream,
+        })
+    }
+
+    /// Exact number of input tokens the request for `messages` would use, counted by the
+    /// API including the system prompt and tool definitions.
+    pub async fn count_input_tokens(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<u64, InferenceError> {
+        let request = self.build_request(messages, system_message, false)?;
+        let request = CountTokensRequest {
+            model: request.model,
+            messages: request.messages,
+            tools: request.tools,
+            system: request.system,
+            thinking: request.thinking,
         };
+        let response = self.send("/messages/count_tokens", &request).await?;
 
+        let response_text = http::read_text(response, self.read_timeout).await?;
+        let count: CountTokensResponse = serde_json::from_str(&response_text)
+            .map_err(|e| InferenceError::InvalidResponse(e.to_string()))?;
+        Ok(count.input_tokens)
+    }
+
+    async fn send(&self, path: &str, request: &impl Serialize) -> Result<reqwest::Response, InferenceError> {
         let response = self.client
-            .post(format!("{}/messages", self.base_url))
+            .post(format!("{}{}", self.base_url, path))
             .header("Content-Type", "application/json")
             .header("X-API-Key", &self.api_key)
             .header("anthropic-version", "2023-06-01")
-            .json(&request)
+            .json(request)
             .send()
             .await
-            .map_err(|e| InferenceError::NetworkError(e.to_string()))?;
+            .map_err(InferenceError::from)?;
 
         let status = response.status();
-        let response_text = response.text().await
-            .map_err(|e| InferenceError::NetworkError(e.to_string()))?;
-        let response_json: serde_json::Value = serde_json::from_str(&response_text).unwrap();
-        log::info!("{:#?}", response_json);
-
         if !status.is_success() {
-            return Err(InferenceError::ApiError(status, response_text));
+            let headers = response.headers().clone();
+            let response_text = http::read_text(response, self.read_timeout).await?;
+            log::info!("{}", response_text);
+            return Err(InferenceError::from_response(status, &headers, response_text));
         }
 
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl Inference for AnthropicInference {
+    async fn query_model(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, InferenceError> {
+        let request = self.build_request(messages, system_message, false)?;
+        let response = self.send("/messages", &request).await?;
+
+        let response_text = http::read_text(response, self.read_timeout).await?;
+        log::info!("{}", response_text);
+
         let anthropic_response: AnthropicResponse = serde_json::from_str(&response_text)
             .map_err(|e| InferenceError::InvalidResponse(e.to_string()))?;
 
@@ -215,12 +275,577 @@ impl AnthropicInference {
             message_type: "text".to_string(),
             stop_reason: anthropic_response.stop_reason,
             stop_sequence: anthropic_response.stop_sequence,
-            //usage: Some(Usage {
-            //    input_tokens: anthropic_response.usage.input_tokens,
-            //    cache_creation_input_tokens: 0,
-            //    cache_read_input_tokens: 0,
-            //    output_tokens: anthropic_response.usage.output_tokens,
-            //}),
+            usage: anthropic_response.usage,
+        })
+    }
+
+    /// Same as `query_model` but with `stream: true`, yielding text and tool use deltas as
+    /// they arrive.  The last event of the stream is `StreamEvent::Done` with the assembled
+    /// response.
+    async fn query_model_stream(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<EventStream, InferenceError> {
+        let request = self.build_request(messages, system_message, true)?;
+        let response = self.send("/messages", &request).await?;
+
+        let state = StreamState {
+            bytes: http::body_stream(response, self.read_timeout),
+            parser: SseParser::new(),
+            accumulator: StreamAccumulator::default(),
+            pending: VecDeque::new(),
+            finished: false,
+        };
+
+        let stream = stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(event) = state.pending.pop_front() {
+                    return Some((event, state));
+                }
+                if state.finished {
+                    return None;
+                }
+
+                match state.bytes.next().await {
+                    Some(Ok(chunk)) => {
+                        for sse in state.parser.push(&chunk) {
+                            match state.accumulator.handle(&sse) {
+                                Ok(events) => {
+                                    state.finished |= events.iter().any(|e| matches!(e, StreamEvent::Done { .. }));
+                                    state.pending.extend(events.into_iter().map(Ok));
+                                },
+                                Err(e) => {
+                                    state.pending.push_back(Err(e));
+                                    state.finished = true;
+                                    break;
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => {
+                        state.pending.push_back(Err(e));
+                        state.finished = true;
+                    },
+                    None => {
+                        state.pending.push_back(Err(InferenceError::InvalidResponse(
+                            "Stream ended before message_stop".to_string()
+                        )));
+                        state.finished = true;
+                    }
+                }
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn count_tokens(&self, messages: Vec<Message>, system_message: Option<&str>) -> Option<Result<u64, InferenceError>> {
+        Some(self.count_input_tokens(messages, system_message).await)
+    }
+}
+
+/// Puts cache breakpoints on the last tool and on the last block of the history.  The
+/// history breakpoint moves forward every turn, the previous turn's prefix is still found
+/// in the cache because lookups walk back from the breakpoint.
+fn add_cache_breakpoints(tools: &mut serde_json::Value, messages: &mut [serde_json::Value]) {
+    let cache_control = serde_json::json!(CacheControl::ephemeral());
+    if let Some(tool) = tools.as_array_mut().and_then(|tools| tools.last_mut()) {
+        tool["cache_control"] = cache_control.clone();
+    }
+    let last_block = messages.last_mut()
+        .and_then(|message| message["content"].as_array_mut())
+        .and_then(|content| content.last_mut());
+    if let Some(block) = last_block {
+        block["cache_control"] = cache_control;
+    }
+}
+
+struct StreamState {
+    bytes: http::ByteStream,
+    parser: SseParser,
+    accumulator: StreamAccumulator,
+    pending: VecDeque<Result<StreamEvent, InferenceError>>,
+    finished: bool,
+}
+
+enum PartialBlock {
+    Text(String),
+    Thinking { thinking: String, signature: String },
+    RedactedThinking(String),
+    ToolUse { id: String, name: String, input_json: String },
+}
+
+/// Folds Anthropic stream events back into the shape of a non-streaming response.
+#[derive(Default)]
+struct StreamAccumulator {
+    id: String,
+    model: String,
+    role: String,
+    blocks: Vec<PartialBlock>,
+    stop_reason: String,
+    stop_sequence: Option<String>,
+    usage: Usage,
+}
+
+impl StreamAccumulator {
+    fn handle(&mut self, sse: &SseEvent) -> Result<Vec<StreamEvent>, InferenceError> {
+        let data: serde_json::Value = serde_json::from_str(&sse.data)
+            .map_err(|e| InferenceError::InvalidResponse(format!("Invalid stream event: {}", e)))?;
+        let event_type = data.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+
+        let mut events = Vec::new();
+        match event_type {
+            "message_start" => {
+                let message = &data["message"];
+                self.id = message["id"].as_str().unwrap_or_default().to_string();
+                self.model = message["model"].as_str().unwrap_or_default().to_string();
+                self.role = message["role"].as_str().unwrap_or("assistant").to_string();
+                if let Ok(usage) = Usage::deserialize(&message["usage"]) {
+                    self.usage = usage;
+                }
+            },
+            "content_block_start" => {
+                let block = &data["content_block"];
+                match block["type"].as_str() {
+                    Some("tool_use") => {
+                        let id = block["id"].as_str().unwrap_or_default().to_string();
+                        let name = block["name"].as_str().unwrap_or_default().to_string();
+                        events.push(StreamEvent::ToolUseStart { id: id.clone(), name: name.clone() });
+                        self.blocks.push(PartialBlock::ToolUse { id, name, input_json: String::new() });
+                    },
+                    Some("thinking") => {
+                        let thinking = block["thinking"].as_str().unwrap_or_default().to_string();
+                        let signature = block["signature"].as_str().unwrap_or_default().to_string();
+                        self.blocks.push(PartialBlock::Thinking { thinking, signature });
+                    },
+                    Some("redacted_thinking") => {
+                        let data = block["data"].as_str().unwrap_or_default().to_string();
+                        self.blocks.push(PartialBlock::RedactedThinking(data));
+                    },
+                    _ => {
+                        let text = block["text"].as_str().unwrap_or_default().to_string();
+                        if !text.is_empty() {
+                            events.push(StreamEvent::TextDelta { text: text.clone() });
+                        }
+                        self.blocks.push(PartialBlock::Text(text));
+                    }
+                }
+            },
+            "content_block_delta" => {
+                let delta = &data["delta"];
+                match (self.blocks.last_mut(), delta["type"].as_str()) {
+                    (Some(PartialBlock::Text(text)), Some("text_delta")) => {
+                        let fragment = delta["text"].as_str().unwrap_or_default();
+                        text.push_str(fragment);
+                        events.push(StreamEvent::TextDelta { text: fragment.to_string() });
+                    },
+                    (Some(PartialBlock::Thinking { thinking, .. }), Some("thinking_delta")) => {
+                        let fragment = delta["thinking"].as_str().unwrap_or_default();
+                        thinking.push_str(fragment);
+                        events.push(StreamEvent::ThinkingDelta { thinking: fragment.to_string() });
+                    },
+                    (Some(PartialBlock::Thinking { signature, .. }), Some("signature_delta")) => {
+                        signature.push_str(delta["signature"].as_str().unwrap_or_default());
+                    },
+                    (Some(PartialBlock::ToolUse { id, input_json, .. }), Some("input_json_delta")) => {
+                        let fragment = delta["partial_json"].as_str().unwrap_or_default();
+                        input_json.push_str(fragment);
+                        events.push(StreamEvent::ToolUseInputDelta {
+                            id: id.clone(),
+                            partial_json: fragment.to_string(),
+                        });
+                    },
+                    _ => log::info!("Ignoring unexpected content block delta: {}", delta),
+                }
+            },
+            "content_block_stop" => {
+                if let Some(PartialBlock::ToolUse { .. }) = self.blocks.last() {
+                    events.push(StreamEvent::ToolUse { tool_use: self.finish_block(self.blocks.len() - 1)? });
+                }
+            },
+            "message_delta" => {
+                let delta = &data["delta"];
+                if let Some(stop_reason) = delta["stop_reason"].as_str() {
+                    self.stop_reason = stop_reason.to_string();
+                }
+                self.stop_sequence = delta["stop_sequence"].as_str().map(String::from);
+                // The delta carries the cumulative output token count
+                if let Some(output_tokens) = data["usage"]["output_tokens"].as_u64() {
+                    self.usage.output_tokens = output_tokens;
+                }
+            },
+            "message_stop" => {
+                events.push(StreamEvent::Done { response: self.finish()? });
+            },
+            "error" => {
+                let status = match data["error"]["type"].as_str() {
+                    Some("overloaded_error") => StatusCode::from_u16(529).unwrap_or(StatusCode::SERVICE_UNAVAILABLE),
+                    Some("rate_limit_error") => StatusCode::TOO_MANY_REQUESTS,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                return Err(InferenceError::ApiError(status, sse.data.clone()));
+            },
+            _ => {} // ping
+        }
+
+        Ok(events)
+    }
+
+    fn finish_block(&self, index: usize) -> Result<ContentItem, InferenceError> {
+        match &self.blocks[index] {
+            PartialBlock::Text(text) => Ok(ContentItem::Text { text: text.clone() }),
+            PartialBlock::Thinking { thinking, signature } => Ok(ContentItem::Thinking {
+                thinking: thinking.clone(),
+                signature: signature.clone(),
+            }),
+            PartialBlock::RedactedThinking(data) => Ok(ContentItem::RedactedThinking { data: data.clone() }),
+            PartialBlock::ToolUse { id, name, input_json } => {
+                // Tools without arguments stream no input deltas at all
+                let input = if input_json.is_empty() {
+                    serde_json::json!({})
+                } else {
+                    serde_json::from_str(input_json)
+                        .map_err(|e| InferenceError::InvalidResponse(format!("Invalid tool input: {}", e)))?
+                };
+                Ok(ContentItem::ToolUse { id: id.clone(), name: name.clone(), input })
+            }
+        }
+    }
+
+    fn finish(&self) -> Result<ModelResponse, InferenceError> {
+        let content = (0..self.blocks.len())
+            .map(|i| self.finish_block(i))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ModelResponse {
+            content,
+            id: self.id.clone(),
+            model: self.model.clone(),
+            role: self.role.clone(),
+            message_type: "text".to_string(),
+            stop_reason: self.stop_reason.clone(),
+            stop_sequence: self.stop_sequence.clone(),
+            usage: self.usage,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::inference::stub_server::{self, StubResponse};
+    use crate::inference::types::Role;
+
+    const RECORDED_STREAM: &str = "event: message_start
+data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_01\",\"type\":\"message\",\"role\":\"assistant\",\"model\":\"claude-3-5-haiku-20241022\",\"content\":[],\"stop_reason\":null,\"usage\":{\"input_tokens\":2095,\"cache_creation_input_tokens\":0,\"cache_read_input_tokens\":1800,\"output_tokens\":1}}}
+
+event: content_block_start
+data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}
+
+event: ping
+data: {\"type\":\"ping\"}
+
+event: content_block_delta
+data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Reading \"}}
+
+event: content_block_delta
+data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"the file.\"}}
+
+event: content_block_stop
+data: {\"type\":\"content_block_stop\",\"index\":0}
+
+event: content_block_start
+data: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_01\",\"name\":\"read_file\",\"input\":{}}}
+
+event: content_block_delta
+data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"path\\\": \\\"src/\"}}
+
+event: content_block_delta
+data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"main.rs\\\"}\"}}
+
+event: content_block_stop
+data: {\"type\":\"content_block_stop\",\"index\":1}
+
+event: message_delta
+data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\",\"stop_sequence\":null},\"usage\":{\"output_tokens\":30}}
+
+event: message_stop
+data: {\"type\":\"message_stop\"}
+
+";
+
+    #[test]
+    fn test_stream_accumulates_text_and_tool_use() -> Result<(), InferenceError> {
+        let mut parser = SseParser::new();
+        let mut accumulator = StreamAccumulator::default();
+        let mut events = Vec::new();
+        for chunk in RECORDED_STREAM.as_bytes().chunks(7) {
+            for sse in parser.push(chunk) {
+                events.extend(accumulator.handle(&sse)?);
+            }
+        }
+
+        let text: String = events.iter()
+            .filter_map(|e| match e {
+                StreamEvent::TextDelta { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(text, "Reading the file.");
+        assert!(matches!(&events[2], StreamEvent::ToolUseStart { name, .. } if name == "read_file"));
+
+        let expected_tool_use = ContentItem::ToolUse {
+            id: "toolu_01".to_string(),
+            name: "read_file".to_string(),
+            input: serde_json::json!({"path": "src/main.rs"}),
+        };
+        assert!(events.iter().any(|e| matches!(e, StreamEvent::ToolUse { tool_use } if *tool_use == expected_tool_use)));
+
+        match events.last() {
+            Some(StreamEvent::Done { response }) => {
+                assert_eq!(response.id, "msg_01");
+                assert_eq!(response.stop_reason, "tool_use");
+                assert_eq!(response.usage, Usage {
+                    input_tokens: 2095,
+                    output_tokens: 30,
+                    cache_creation_input_tokens: 0,
+                    cache_read_input_tokens: 1800,
+                });
+                assert_eq!(response.content, vec![
+                    ContentItem::Text { text: "Reading the file.".to_string() },
+                    expected_tool_use,
+                ]);
+            },
+            other => panic!("Expected Done event, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_model_sets_cache_breakpoints() -> Result<(), InferenceError> {
+        let (base_url, requests) = stub_server::spawn(vec![StubResponse::json(r#"{
+            "id": "msg_02",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-5-haiku-20241022",
+            "content": [{"type": "text", "text": "Done."}],
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 12, "cache_creation_input_tokens": 350, "cache_read_input_tokens": 4200, "output_tokens": 3}
+        }"#)]).await;
+
+        let config = ProjectConfig {
+            base_url,
+            api_key: "test-key".to_string(),
+            ..ProjectConfig::default()
+        };
+        let history = vec![
+            Message {
+                role: Role::User,
+                content: vec![ContentItem::Text { text: "Read main.rs".to_string() }],
+                model: None,
+            },
+            Message {
+                role: Role::Assistant,
+                content: vec![ContentItem::ToolUse {
+                    id: "toolu_01".to_string(),
+                    name: "read_file".to_string(),
+                    input: json!({"path": "src/main.rs"}),
+                }],
+                model: None,
+            },
+            Message {
+                role: Role::User,
+                content: vec![ContentItem::ToolResult {
+                    tool_use_id: "toolu_01".to_string(),
+                    content: "fn main() {}".to_string(),
+                }],
+                model: None,
+            },
+        ];
+
+        let response = AnthropicInference::from_config(&config).query_model(history, Some("File tree: src/main.rs")).await?;
+        assert_eq!(response.usage.cache_creation_input_tokens, 350);
+        assert_eq!(response.usage.cache_read_input_tokens, 4200);
+
+        let body = requests.lock().unwrap()[0].json();
+        let ephemeral = json!({"type": "ephemeral"});
+        assert_eq!(body["system"], json!([{
+            "type": "text",
+            "text": "File tree: src/main.rs",
+            "cache_control": ephemeral,
+        }]));
+        let tools = body["tools"].as_array().unwrap();
+        assert_eq!(tools.last().unwrap()["cache_control"], ephemeral);
+        assert!(tools[..tools.len() - 1].iter().all(|tool| tool.get("cache_control").is_none()));
+        assert!(body["messages"][0]["content"][0].get("cache_control").is_none());
+        assert_eq!(body["messages"][2]["content"][0]["cache_control"], ephemeral);
+        assert_eq!(body["messages"][2]["content"][0]["tool_use_id"], "toolu_01");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_count_tokens_sends_the_whole_prompt() -> Result<(), InferenceError> {
+        let (base_url, requests) = stub_server::spawn(vec![StubResponse::json(r#"{"input_tokens": 2095}"#)]).await;
+        let config = ProjectConfig {
+            base_url,
+            api_key: "test-key".to_string(),
+            ..ProjectConfig::default()
+        };
+        let history = vec![Message {
+            role: Role::User,
+            content: vec![ContentItem::Text { text: "Add a GET /ping endpoint".to_string() }],
+            model: None,
+        }];
+
+        let tokens = AnthropicInference::from_config(&config).count_input_tokens(history, Some("File tree: src/main.rs")).await?;
+        assert_eq!(tokens, 2095);
+
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.request_line.starts_with("POST /messages/count_tokens "));
+        let body = request.json();
+        assert_eq!(body["system"][0]["text"], "File tree: src/main.rs");
+        assert!(!body["tools"].as_array().unwrap().is_empty());
+        assert!(body.get("max_tokens").is_none());
+        assert!(body.get("stream").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sampling_conflicting_with_thinking_is_rejected() {
+        use crate::config::SamplingConfig;
+
+        let config = ProjectConfig {
+            api_key: "test-key".to_string(),
+            sampling: SamplingConfig { top_k: Some(40), tool_choice: Some(ToolChoice::Any), ..SamplingConfig::default() },
+            ..ProjectConfig::default()
+        };
+        let inference = AnthropicInference::from_config(&config);
+        let body = serde_json::to_value(inference.build_request(Vec::new(), None, false).unwrap()).unwrap();
+        assert_eq!(body["top_k"], 40);
+        assert_eq!(body["tool_choice"], json!({"type": "any"}));
+        assert!(body.get("temperature").is_none());
+
+        let thinking = ProjectConfig { thinking_budget: Some(2048), ..config };
+        let error = AnthropicInference::from_config(&thinking).build_request(Vec::new(), None, false).err();
+        assert!(matches!(error, Some(InferenceError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_stream_collects_signed_thinking() -> Result<(), InferenceError> {
+        let recorded = [
+            r#"{"type":"message_start","message":{"id":"msg_03","role":"assistant","model":"claude-3-7-sonnet-20250219","usage":{"input_tokens":40,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"thinking","thinking":""}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"The user wants "}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"a greeting."}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"signature_delta","signature":"EqQBCgIYAhIM"}}"#,
+            r#"{"type":"content_block_stop","index":0}"#,
+            r#"{"type":"content_block_start","index":1,"content_block":{"type":"redacted_thinking","data":"EmwKAhgBEgy3"}}"#,
+            r#"{"type":"content_block_stop","index":1}"#,
+            r#"{"type":"content_block_start","index":2,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"content_block_delta","index":2,"delta":{"type":"text_delta","text":"Hello!"}}"#,
+            r#"{"type":"content_block_stop","index":2}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":20}}"#,
+            r#"{"type":"message_stop"}"#,
+        ];
+
+        let mut accumulator = StreamAccumulator::default();
+        let mut events = Vec::new();
+        for data in recorded {
+            events.extend(accumulator.handle(&SseEvent { event: None, data: data.to_string() })?);
+        }
+
+        let thinking: String = events.iter()
+            .filter_map(|e| match e {
+                StreamEvent::ThinkingDelta { thinking } => Some(thinking.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(thinking, "The user wants a greeting.");
+        assert!(!events.iter().any(|e| matches!(e, StreamEvent::TextDelta { text } if text.contains("greeting"))));
+
+        match events.last() {
+            Some(StreamEvent::Done { response }) => assert_eq!(response.content, vec![
+                ContentItem::Thinking {
+                    thinking: "The user wants a greeting.".to_string(),
+                    signature: "EqQBCgIYAhIM".to_string(),
+                },
+                ContentItem::RedactedThinking { data: "EmwKAhgBEgy3".to_string() },
+                ContentItem::Text { text: "Hello!".to_string() },
+            ]),
+            other => panic!("Expected Done event, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_model_replays_only_signed_thinking() -> Result<(), InferenceError> {
+        let (base_url, requests) = stub_server::spawn(vec![StubResponse::json(r#"{
+            "id": "msg_04",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-7-sonnet-20250219",
+            "content": [
+                {"type": "thinking", "thinking": "It compiles.", "signature": "EqQBCgIYAhIN"},
+                {"type": "text", "text": "All good."}
+            ],
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 80, "output_tokens": 30}
+        }"#)]).await;
+
+        let config = ProjectConfig {
+            base_url,
+            api_key: "test-key".to_string(),
+            max_output_tokens: 4096,
+            thinking_budget: Some(2048),
+            ..ProjectConfig::default()
+        };
+        let signed = ContentItem::Thinking {
+            thinking: "I should compile.".to_string(),
+            signature: "EqQBCgIYAhIM".to_string(),
+        };
+        let history = vec![
+            Message {
+                role: Role::User,
+                content: vec![ContentItem::Text { text: "Check it".to_string() }],
+                model: None,
+            },
+            Message {
+                role: Role::Assistant,
+                content: vec![
+                    ContentItem::Thinking { thinking: "From another provider".to_string(), signature: String::new() },
+                    signed.clone(),
+                    ContentItem::ToolUse {
+                        id: "toolu_02".to_string(),
+                        name: "compile_check".to_string(),
+                        input: json!({}),
+                    },
+                ],
+                model: None,
+            },
+            Message {
+                role: Role::User,
+                content: vec![ContentItem::ToolResult {
+                    tool_use_id: "toolu_02".to_string(),
+                    content: "ok".to_string(),
+                }],
+                model: None,
+            },
+        ];
+
+        let response = AnthropicInference::from_config(&config).query_model(history, None).await?;
+        assert_eq!(response.content[0], ContentItem::Thinking {
+            thinking: "It compiles.".to_string(),
+            signature: "EqQBCgIYAhIN".to_string(),
+        });
+
+        let body = requests.lock().unwrap()[0].json();
+        assert_eq!(body["thinking"], json!({"type": "enabled", "budget_tokens": 2048}));
+        assert_eq!(body["max_tokens"], 4096 + 2048);
+        assert!(body.get("system").is_none());
+        let assistant = body["messages"][1]["content"].as_array().unwrap();
+        assert_eq!(assistant.len(), 2);
+        assert_eq!(assistant[0], serde_json::to_value(&signed).unwrap());
+        assert_eq!(assistant[1]["type"], "tool_use");
+        Ok(())
+    }
+}
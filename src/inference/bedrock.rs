@@ -1,181 +1,494 @@
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use async_trait::async_trait;
 use anyhow::Result;
 use aws_sdk_bedrockruntime::Client as BedrockClient;
-use aws_sdk_bedrockruntime::primitives::Blob;
+use aws_sdk_bedrockruntime::config::http::HttpResponse;
+use aws_sdk_bedrockruntime::config::Region;
+use aws_sdk_bedrockruntime::error::{DisplayErrorContext, SdkError};
+use aws_sdk_bedrockruntime::operation::converse::ConverseOutput;
+use aws_sdk_bedrockruntime::types::{
+    AnyToolChoice, AutoToolChoice, CachePointBlock, CachePointType, ContentBlock, ConversationRole, ConverseOutput as ConverseMessage,
+    ImageBlock, ImageFormat, ImageSource as BedrockImageSource, InferenceConfiguration,
+    Message as BedrockMessage, ReasoningContentBlock, ReasoningTextBlock, SpecificToolChoice, SystemContentBlock, Tool,
+    ToolChoice as BedrockToolChoice, ToolConfiguration, ToolInputSchema, ToolResultBlock, ToolResultContentBlock, ToolSpecification, ToolUseBlock,
+};
+use aws_smithy_types::{base64, Blob, Document, Number};
+use reqwest::StatusCode;
 use serde_json::json;
 
-use super::types::{Message, ModelResponse, Inference, InferenceError};
-use super::tools::{AnthropicTool, InputSchema, PropertySchema};
+use super::types::{ContentItem, Message, ModelResponse, Inference, InferenceError, Role, Usage};
+use crate::config::{ProjectConfig, SamplingConfig, ToolChoice};
+use crate::tools::ToolRegistry;
 
+/// Talks to Bedrock through the Converse API, which gives every model family (Anthropic,
+/// Llama, Mistral, Nova, Cohere, ...) the same request shape including tool use.
 pub struct AWSBedrockInference {
-    client: Arc<BedrockClient>, 
+    client: Arc<BedrockClient>,
     model_id: String,
-    temperature: f32,
     max_tokens: Option<i32>,
+    thinking_budget: Option<u32>,
+    sampling: SamplingConfig,
+    tools: ToolRegistry,
 }
 
 impl AWSBedrockInference {
-    pub async fn new(
-        model_id: String,
-        temperature: f32,
-        max_tokens: Option<i32>,
-    ) -> Result<Self> {
-        let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
-        let client = Arc::new(BedrockClient::new(&config));
-
-        Ok(Self {
-            client,
-            model_id,
-            temperature,
-            max_tokens,
-        })
-    }
+    /// Loads AWS credentials and region the usual way (environment, profiles, instance
+    /// metadata) with any overrides from the `[bedrock]` table applied on top.
+    pub async fn from_config(config: &ProjectConfig) -> Self {
+        let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+        if let Some(profile) = &config.bedrock.profile {
+            loader = loader.profile_name(profile);
+        }
+        if let Some(region) = &config.bedrock.region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        if let Some(endpoint_url) = &config.bedrock.endpoint_url {
+            loader = loader.endpoint_url(endpoint_url);
+        }
+        // The SDK's read timeout covers the wait for the first byte, which Converse only
+        // sends once the model is done, so only connecting and the whole call are bounded
+        loader = loader.timeout_config(aws_config::timeout::TimeoutConfig::builder()
+            .connect_timeout(config.timeouts.connect())
+            .operation_timeout(config.timeouts.request())
+            .build());
+        let sdk_config = loader.load().await;
 
-    fn get_anthropic_tools(&self) -> Vec<AnthropicTool> {
-        vec![
-            self.read_file_tool(),
-            self.write_file_tool(),
-            self.execute_tool(),
-            self.compile_check_tool(),
-        ]
+        Self::with_client(BedrockClient::new(&sdk_config), config)
     }
 
-    fn read_file_tool(&self) -> AnthropicTool {
-        AnthropicTool {
-            name: "read_file".to_string(),
-            description: "Read file as string using path relative to root directory of project.".to_string(),
-            input_schema: InputSchema {
-                schema_type: "object".to_string(),
-                properties: {
-                    let mut map = HashMap::new();
-                    map.insert(
-                        "path".to_string(),
-                        PropertySchema {
-                            property_type: "string".to_string(),
-                            description: "The file path relative to the project root directory".to_string(),
-                        },
-                    );
-                    map
-                },
-                required: vec!["path".to_string()],
-            },
+    pub fn with_client(client: BedrockClient, config: &ProjectConfig) -> Self {
+        Self {
+            client: Arc::new(client),
+            model_id: config.model.clone(),
+            max_tokens: Some(config.max_output_tokens as i32),
+            thinking_budget: config.thinking_budget,
+            sampling: config.sampling.clone(),
+            tools: ToolRegistry::default(),
         }
     }
 
-    fn write_file_tool(&self) -> AnthropicTool {
-        AnthropicTool {
-            name: "write_file".to_string(),
-            description: "Write string to file at path relative to root directory of project.".to_string(),
-            input_schema: InputSchema {
-                schema_type: "object".to_string(),
-                properties: {
-                    let mut map = HashMap::new();
-                    map.insert(
-                        "path".to_string(),
-                        PropertySchema {
-                            property_type: "string".to_string(),
-                            description: "The file path relative to the project root directory".to_string(),
-                        },
-                    );
-                    map.insert(
-                        "content".to_string(),
-                        PropertySchema {
-                            property_type: "string".to_string(),
-                            description: "The content to write to the file".to_string(),
-                        },
-                    );
-                    map
-                },
-                required: vec!["path".to_string(), "content".to_string()],
-            },
-        }
+    /// Cache points are only accepted for Anthropic models
+    fn supports_prompt_caching(&self) -> bool {
+        self.model_id.contains("anthropic")
     }
 
-    fn execute_tool(&self) -> AnthropicTool {
-        AnthropicTool {
-            name: "execute".to_string(),
-            description: "Execute bash statements as a single string..".to_string(),
-            input_schema: InputSchema {
-                schema_type: "object".to_string(),
-                properties: {
-                    let mut map = HashMap::new();
-                    map.insert(
-                        "statement".to_string(),
-                        PropertySchema {
-                            property_type: "string".to_string(),
-                            description: "The bash statement to be executed.".to_string(),
-                        },
-                    );
-                    map
-                },
-                required: vec!["statement".to_string()],
-            },
+    /// Extended thinking is only passed on to Anthropic models
+    fn thinking_budget(&self) -> Option<u32> {
+        self.thinking_budget.filter(|_| self.model_id.contains("anthropic"))
+    }
+
+    /// Converse has no top_k, it is passed on to Anthropic models as a model specific field.
+    fn validate_sampling(&self) -> Result<(), InferenceError> {
+        let mut supported = vec!["temperature", "top_p", "stop_sequences", "tool_choice"];
+        if self.model_id.contains("anthropic") {
+            supported.push("top_k");
         }
+        self.sampling.validate("bedrock", &supported)?;
+        if self.thinking_budget().is_some() && (self.sampling.temperature.is_some() || self.sampling.top_k.is_some()) {
+            return Err(InferenceError::InvalidConfig("temperature and top_k can't be set with thinking_budget".to_string()));
+        }
+        if self.sampling.tool_choice == Some(ToolChoice::None) {
+            return Err(InferenceError::InvalidConfig("bedrock does not support tool_choice none".to_string()));
+        }
+        Ok(())
     }
 
-    fn compile_check_tool(&self) -> AnthropicTool {
-        AnthropicTool {
-            name: "compile_check".to_string(),
-            description: "Check if project compiles or runs without error.".to_string(),
-            input_schema: InputSchema {
-                schema_type: "object".to_string(),
-                properties: {
-                    let mut map = HashMap::new();
-                    map.insert(
-                        "cmd".to_string(),
-                        PropertySchema {
-                            property_type: "string".to_string(),
-                            description: "The command to check for compiler/interpreter errors.".to_string(),
-                        },
-                    );
-                    map
-                },
-                required: vec!["cmd".to_string()],
-            },
+    fn tool_choice(&self) -> Result<Option<BedrockToolChoice>, InferenceError> {
+        Ok(match &self.sampling.tool_choice {
+            Some(ToolChoice::Auto) => Some(BedrockToolChoice::Auto(AutoToolChoice::builder().build())),
+            Some(ToolChoice::Any) => Some(BedrockToolChoice::Any(AnyToolChoice::builder().build())),
+            Some(ToolChoice::Tool(name)) => Some(BedrockToolChoice::Tool(SpecificToolChoice::builder()
+                .name(name)
+                .build()
+                .map_err(|e| InferenceError::SerializationError(e.to_string()))?)),
+            Some(ToolChoice::None) | None => None,
+        })
+    }
+
+    fn tool_configuration(&self) -> Result<ToolConfiguration, InferenceError> {
+        let mut tools = self.tools.iter()
+            .map(|tool| {
+                let schema = serde_json::to_value(tool.input_schema())
+                    .map_err(|e| InferenceError::SerializationError(e.to_string()))?;
+                ToolSpecification::builder()
+                    .name(tool.name())
+                    .description(tool.description())
+                    .input_schema(ToolInputSchema::Json(to_document(&schema)))
+                    .build()
+                    .map(Tool::ToolSpec)
+                    .map_err(|e| InferenceError::SerializationError(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if self.supports_prompt_caching() {
+            tools.push(Tool::CachePoint(cache_point()?));
         }
+
+        ToolConfiguration::builder()
+            .set_tools(Some(tools))
+            .set_tool_choice(self.tool_choice()?)
+            .build()
+            .map_err(|e| InferenceError::SerializationError(e.to_string()))
     }
 }
 
+#[async_trait]
 impl Inference for AWSBedrockInference {
     async fn query_model(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, InferenceError> {
-        let body = if self.model_id.contains("anthropic") {
-            // Anthropic Claude models
-            let tools_json = match serde_json::to_value(self.get_anthropic_tools()) {
-                Ok(tools) => tools,
-                Err(_) => json!(null),
-            };
-            let sys_msg = match system_message {
-                Some(m) => m,
-                None => "",
-            };
-
-            json!({
-                "anthropic_version": "bedrock-2023-05-31",
-                "system": sys_msg,
-                "messages": messages,
-                "max_tokens": self.max_tokens.unwrap_or(2000),
-                "temperature": self.temperature,
-                "tools": tools_json
+        self.validate_sampling()?;
+        let mut messages = to_bedrock_messages(messages)?;
+        if self.supports_prompt_caching() {
+            // Same breakpoints as the Anthropic API: tools, system prompt and history
+            if let Some(last) = messages.last_mut() {
+                last.content.push(ContentBlock::CachePoint(cache_point()?));
+            }
+        }
+
+        let mut request = self.client
+            .converse()
+            .model_id(&self.model_id)
+            .set_messages(Some(messages))
+            .tool_config(self.tool_configuration()?);
+        // Thinking tokens count towards max_tokens, same as the Anthropic API
+        let max_tokens = match self.thinking_budget() {
+            Some(budget) => self.max_tokens.map(|max_tokens| max_tokens + budget as i32),
+            None => self.max_tokens,
+        };
+        request = request.inference_config(InferenceConfiguration::builder()
+            .set_max_tokens(max_tokens)
+            .set_temperature(self.sampling.temperature)
+            .set_top_p(self.sampling.top_p)
+            .set_stop_sequences(Some(self.sampling.stop_sequences.clone()).filter(|stop| !stop.is_empty()))
+            .build());
+
+        let mut model_fields = serde_json::Map::new();
+        if let Some(budget) = self.thinking_budget() {
+            model_fields.insert("thinking".to_string(), json!({ "type": "enabled", "budget_tokens": budget }));
+        }
+        if let Some(top_k) = self.sampling.top_k {
+            model_fields.insert("top_k".to_string(), json!(top_k));
+        }
+        if !model_fields.is_empty() {
+            request = request.additional_model_request_fields(to_document(&serde_json::Value::Object(model_fields)));
+        }
+        if let Some(sys_msg) = system_message {
+            request = request.system(SystemContentBlock::Text(sys_msg.to_string()));
+            if self.supports_prompt_caching() {
+                request = request.system(SystemContentBlock::CachePoint(cache_point()?));
+            }
+        }
+
+        let response = request.send().await.map_err(sdk_error)?;
+        from_converse_output(response, &self.model_id)
+    }
+}
+
+fn unique_suffix() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos()
+}
+
+fn cache_point() -> Result<CachePointBlock, InferenceError> {
+    CachePointBlock::builder()
+        .r#type(CachePointType::Default)
+        .build()
+        .map_err(|e| InferenceError::SerializationError(e.to_string()))
+}
+
+/// Translates chat history into Converse messages.  Converse only knows user and assistant
+/// turns, anything else is sent as the user.
+fn to_bedrock_messages(messages: Vec<Message>) -> Result<Vec<BedrockMessage>, InferenceError> {
+    let build_error = |e: aws_smithy_types::error::operation::BuildError| InferenceError::SerializationError(e.to_string());
+    let mut bedrock_messages = Vec::new();
+
+    for msg in messages {
+        let role = match msg.role {
+            Role::Assistant => ConversationRole::Assistant,
+            _ => ConversationRole::User,
+        };
+
+        let content = msg.content.into_iter()
+            .filter(|item| !item.is_unsigned_thinking())
+            .map(|item| match item {
+                ContentItem::Text { text } => Ok(ContentBlock::Text(text)),
+                ContentItem::ToolUse { id, name, input } => ToolUseBlock::builder()
+                    .tool_use_id(id)
+                    .name(name)
+                    .input(to_document(&input))
+                    .build()
+                    .map(ContentBlock::ToolUse)
+                    .map_err(build_error),
+                ContentItem::ToolResult { tool_use_id, content } => ToolResultBlock::builder()
+                    .tool_use_id(tool_use_id)
+                    .content(ToolResultContentBlock::Text(content))
+                    .build()
+                    .map(ContentBlock::ToolResult)
+                    .map_err(build_error),
+                ContentItem::Thinking { thinking, signature } => ReasoningTextBlock::builder()
+                    .text(thinking)
+                    .signature(signature)
+                    .build()
+                    .map(|reasoning| ContentBlock::ReasoningContent(ReasoningContentBlock::ReasoningText(reasoning)))
+                    .map_err(build_error),
+                ContentItem::Image { source } => {
+                    let (media_type, data) = source.base64()
+                        .ok_or_else(|| InferenceError::UnsupportedContent("Image was not loaded".to_string()))?;
+                    let bytes = base64::decode(data)
+                        .map_err(|e| InferenceError::SerializationError(e.to_string()))?;
+                    ImageBlock::builder()
+                        .format(ImageFormat::from(media_type.trim_start_matches("image/")))
+                        .source(BedrockImageSource::Bytes(Blob::new(bytes)))
+                        .build()
+                        .map(ContentBlock::Image)
+                        .map_err(build_error)
+                },
+                ContentItem::RedactedThinking { data } => base64::decode(&data)
+                    .map(|bytes| ContentBlock::ReasoningContent(ReasoningContentBlock::RedactedContent(Blob::new(bytes))))
+                    .map_err(|e| InferenceError::SerializationError(e.to_string())),
             })
-        } else {
-            return Err(InferenceError::InvalidResponse(format!("Unsupported model: {}", self.model_id)));
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !content.is_empty() {
+            bedrock_messages.push(BedrockMessage::builder()
+                .role(role)
+                .set_content(Some(content))
+                .build()
+                .map_err(build_error)?);
+        }
+    }
+
+    Ok(bedrock_messages)
+}
+
+fn from_converse_output(output: ConverseOutput, model_id: &str) -> Result<ModelResponse, InferenceError> {
+    let message = match output.output {
+        Some(ConverseMessage::Message(message)) => message,
+        _ => return Err(InferenceError::InvalidResponse("No message in Bedrock response".to_string())),
+    };
+
+    let content = message.content.into_iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text(text) => Some(ContentItem::Text { text }),
+            ContentBlock::ToolUse(tool_use) => Some(ContentItem::ToolUse {
+                id: tool_use.tool_use_id,
+                name: tool_use.name,
+                input: from_document(&tool_use.input),
+            }),
+            ContentBlock::ReasoningContent(ReasoningContentBlock::ReasoningText(reasoning)) => Some(ContentItem::Thinking {
+                thinking: reasoning.text,
+                signature: reasoning.signature.unwrap_or_default(),
+            }),
+            // Bedrock hands out redacted reasoning as raw bytes, the Anthropic API as base64
+            ContentBlock::ReasoningContent(ReasoningContentBlock::RedactedContent(data)) => Some(ContentItem::RedactedThinking {
+                data: base64::encode(data.into_inner()),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    let usage = output.usage
+        .map(|usage| {
+            let cache_read = usage.cache_read_input_tokens.unwrap_or(0).max(0) as u64;
+            let cache_write = usage.cache_write_input_tokens.unwrap_or(0).max(0) as u64;
+            Usage {
+                input_tokens: usage.input_tokens.max(0) as u64,
+                output_tokens: usage.output_tokens.max(0) as u64,
+                cache_creation_input_tokens: cache_write,
+                cache_read_input_tokens: cache_read,
+            }
+        })
+        .unwrap_or_default();
+
+    Ok(ModelResponse {
+        content,
+        id: format!("bedrock_{}", unique_suffix()),
+        model: model_id.to_string(),
+        role: "assistant".to_string(),
+        message_type: "text".to_string(),
+        stop_reason: output.stop_reason.as_str().to_string(),
+        stop_sequence: None,
+        usage,
+    })
+}
+
+fn to_document(value: &serde_json::Value) -> Document {
+    match value {
+        serde_json::Value::Null => Document::Null,
+        serde_json::Value::Bool(b) => Document::Bool(*b),
+        serde_json::Value::Number(n) => Document::Number(match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => Number::PosInt(u),
+            (None, Some(i)) => Number::NegInt(i),
+            _ => Number::Float(n.as_f64().unwrap_or_default()),
+        }),
+        serde_json::Value::String(s) => Document::String(s.clone()),
+        serde_json::Value::Array(items) => Document::Array(items.iter().map(to_document).collect()),
+        serde_json::Value::Object(map) => Document::Object(
+            map.iter().map(|(key, value)| (key.clone(), to_document(value))).collect()
+        ),
+    }
+}
+
+fn from_document(document: &Document) -> serde_json::Value {
+    match document {
+        Document::Null => serde_json::Value::Null,
+        Document::Bool(b) => json!(b),
+        Document::Number(Number::PosInt(u)) => json!(u),
+        Document::Number(Number::NegInt(i)) => json!(i),
+        Document::Number(Number::Float(f)) => json!(f),
+        Document::String(s) => json!(s),
+        Document::Array(items) => serde_json::Value::Array(items.iter().map(from_document).collect()),
+        Document::Object(map) => serde_json::Value::Object(
+            map.iter().map(|(key, value)| (key.clone(), from_document(value))).collect()
+        ),
+    }
+}
+
+/// Keeps the HTTP status of failed Bedrock calls so throttling can be retried like any
+/// other provider's rate limit.
+fn sdk_error<E: std::error::Error + 'static>(error: SdkError<E, HttpResponse>) -> InferenceError {
+    let message = DisplayErrorContext(&error).to_string();
+    if matches!(error, SdkError::TimeoutError(_)) {
+        return InferenceError::Timeout(message);
+    }
+    let status = error.raw_response()
+        .and_then(|response| StatusCode::from_u16(response.status().as_u16()).ok());
+    match status {
+        Some(status) if status == StatusCode::TOO_MANY_REQUESTS => InferenceError::RateLimited(status, message, None),
+        Some(status) if status.is_client_error() || status.is_server_error() => InferenceError::ApiError(status, message),
+        _ => InferenceError::NetworkError(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_bedrockruntime::config::{BehaviorVersion, Credentials};
+    use crate::inference::stub_server::{self, StubResponse};
+
+    #[tokio::test]
+    async fn test_query_model_uses_converse() -> Result<(), InferenceError> {
+        let (endpoint_url, requests) = stub_server::spawn(vec![StubResponse::json(r#"{
+            "output": {
+                "message": {
+                    "role": "assistant",
+                    "content": [
+                        {"text": "Checking the build."},
+                        {"toolUse": {"toolUseId": "tooluse_1", "name": "compile_check", "input": {"cmd": "cargo check", "timeout": 30}}}
+                    ]
+                }
+            },
+            "stopReason": "tool_use",
+            "usage": {"inputTokens": 120, "outputTokens": 18, "totalTokens": 138},
+            "metrics": {"latencyMs": 250}
+        }"#)]).await;
+
+        let config = ProjectConfig {
+            provider: "bedrock".to_string(),
+            model: "meta.llama3-1-70b-instruct-v1:0".to_string(),
+            max_output_tokens: 1024,
+            ..ProjectConfig::default()
         };
+        let sdk_config = aws_sdk_bedrockruntime::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-west-2"))
+            .credentials_provider(Credentials::new("AKIDTEST", "secret", None, None, "test"))
+            .endpoint_url(endpoint_url)
+            .build();
+        let inference = AWSBedrockInference::with_client(BedrockClient::from_conf(sdk_config), &config);
 
-        let response = self.client
-            .invoke_model()
-            .model_id(&self.model_id)
-            .accept("application/json")
-            .content_type("application/json")
-            .body(Blob::new(serde_json::to_string(&body).map_err(|e| 
-                InferenceError::SerializationError(e.to_string())
-            )?.into_bytes()))
-            .send()
-            .await
-            .map_err(|e| InferenceError::NetworkError(e.to_string()))?;
-
-        ModelResponse::from_bytes(&response.body.into_inner())
-            .map_err(|e| InferenceError::InvalidResponse(e.to_string()))
+        let history = vec![
+            Message {
+                role: Role::User,
+                content: vec![ContentItem::Text { text: "Read main.rs".to_string() }],
+                model: None,
+            },
+            Message {
+                role: Role::Assistant,
+                content: vec![ContentItem::ToolUse {
+                    id: "tooluse_0".to_string(),
+                    name: "read_file".to_string(),
+                    input: json!({"path": "src/main.rs"}),
+                }],
+                model: None,
+            },
+            Message {
+                role: Role::User,
+                content: vec![ContentItem::ToolResult {
+                    tool_use_id: "tooluse_0".to_string(),
+                    content: "fn main() {}".to_string(),
+                }],
+                model: None,
+            },
+        ];
+
+        let response = inference.query_model(history, Some("Be brief.")).await?;
+
+        assert_eq!(response.stop_reason, "tool_use");
+        assert_eq!(response.model, "meta.llama3-1-70b-instruct-v1:0");
+        assert_eq!(response.usage.input_tokens, 120);
+        assert_eq!(response.usage.output_tokens, 18);
+        assert_eq!(response.content[0], ContentItem::Text { text: "Checking the build.".to_string() });
+        assert_eq!(response.content[1], ContentItem::ToolUse {
+            id: "tooluse_1".to_string(),
+            name: "compile_check".to_string(),
+            input: json!({"cmd": "cargo check", "timeout": 30}),
+        });
+
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.request_line.starts_with("POST /model/meta.llama3-1-70b-instruct-v1%3A0/converse "));
+        assert!(request.header("authorization").is_some_and(|auth| auth.contains("Credential=AKIDTEST/")));
+
+        let body = request.json();
+        assert_eq!(body["system"], json!([{ "text": "Be brief." }]));
+        assert_eq!(body["inferenceConfig"]["maxTokens"], 1024);
+        assert!(body["inferenceConfig"].get("temperature").is_none());
+        let tools = body["toolConfig"]["tools"].as_array().unwrap();
+        assert!(tools.iter().any(|tool| tool["toolSpec"]["name"] == "read_file"
+            && tool["toolSpec"]["inputSchema"]["json"]["required"] == json!(["path"])));
+        assert_eq!(body["messages"][1]["content"][0]["toolUse"]["input"]["path"], "src/main.rs");
+        assert_eq!(body["messages"][2]["content"][0]["toolResult"], json!({
+            "toolUseId": "tooluse_0",
+            "content": [{ "text": "fn main() {}" }],
+        }));
+        assert!(!body.to_string().contains("cachePoint"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_models_get_cache_points() -> Result<(), InferenceError> {
+        let (endpoint_url, requests) = stub_server::spawn(vec![StubResponse::json(r#"{
+            "output": {"message": {"role": "assistant", "content": [{"text": "Hello."}]}},
+            "stopReason": "end_turn",
+            "usage": {"inputTokens": 5, "outputTokens": 2, "totalTokens": 3007, "cacheReadInputTokens": 3000, "cacheWriteInputTokens": 0},
+            "metrics": {"latencyMs": 90}
+        }"#)]).await;
+
+        let config = ProjectConfig {
+            provider: "bedrock".to_string(),
+            model: "anthropic.claude-3-5-haiku-20241022-v1:0".to_string(),
+            ..ProjectConfig::default()
+        };
+        let sdk_config = aws_sdk_bedrockruntime::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-west-2"))
+            .credentials_provider(Credentials::new("AKIDTEST", "secret", None, None, "test"))
+            .endpoint_url(endpoint_url)
+            .build();
+        let inference = AWSBedrockInference::with_client(BedrockClient::from_conf(sdk_config), &config);
+
+        let history = vec![Message {
+            role: Role::User,
+            content: vec![ContentItem::Text { text: "Hi".to_string() }],
+            model: None,
+        }];
+        let response = inference.query_model(history, Some("File tree: src/main.rs")).await?;
+        assert_eq!(response.usage.cache_read_input_tokens, 3000);
+
+        let body = requests.lock().unwrap()[0].json();
+        let cache_point = json!({"cachePoint": {"type": "default"}});
+        assert_eq!(body["system"], json!([{ "text": "File tree: src/main.rs" }, cache_point]));
+        assert_eq!(body["toolConfig"]["tools"].as_array().unwrap().last(), Some(&cache_point));
+        assert_eq!(body["messages"][0]["content"], json!([{ "text": "Hi" }, cache_point]));
+        Ok(())
     }
 }
@@ -1,13 +1,18 @@
-use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Serialize, Deserialize};
 use anyhow::Result;
 
-use crate::config::ProjectConfig;
+use crate::config::{ProjectConfig, SamplingConfig};
+use super::http;
 use super::types::{
-    ContentItem, InferenceError, Message, ModelResponse, Role
+    ContentItem, Inference, InferenceError, Message, ModelResponse, Role, Usage
 };
-use super::tools::{OpenAITool, OpenAIToolFunction, InputSchema, PropertySchema};
+use crate::tools::ToolRegistry;
+use super::tools::OpenAITool;
+use super::openai::{openai_tool_choice, to_openai_messages};
 
 #[derive(Serialize)]
 struct DeepSeekRequest {
@@ -15,6 +20,14 @@ struct DeepSeekRequest {
     messages: Vec<serde_json::Value>,
     max_tokens: Option<u32>,
     tools: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,6 +35,34 @@ struct DeepSeekResponse {
     id: String,
     model: String,
     choices: Vec<DeepSeekChoice>,
+    #[serde(default)]
+    usage: Option<DeepSeekUsage>,
+}
+
+/// Compatible endpoints may leave out the cache counters, or all of it
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DeepSeekUsage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    prompt_cache_hit_tokens: u64,
+    prompt_cache_miss_tokens: u64,
+}
+
+impl From<DeepSeekUsage> for Usage {
+    fn from(usage: DeepSeekUsage) -> Self {
+        // prompt_tokens is the hits plus the misses, when it is there
+        let input_tokens = match usage.prompt_tokens {
+            0 => usage.prompt_cache_miss_tokens,
+            prompt_tokens => prompt_tokens.saturating_sub(usage.prompt_cache_hit_tokens),
+        };
+        Usage {
+            input_tokens,
+            output_tokens: usage.completion_tokens,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: usage.prompt_cache_hit_tokens,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -57,6 +98,9 @@ struct DeepSeekMessage {
     content: Vec<ContentItem>,
     #[serde(default)]
     tool_calls: Option<Vec<DeepSeekToolCall>>,
+    /// Chain of thought of deepseek-reasoner
+    #[serde(default)]
+    reasoning_content: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -76,194 +120,60 @@ struct DeepSeekFunctionCall {
 pub struct DeepSeekInference {
     model: String,
     client: Client,
+    read_timeout: Duration,
     base_url: String,
     api_key: String,
     max_output_tokens: u32,
+    sampling: SamplingConfig,
+    tools: ToolRegistry,
 }
 
 impl std::default::Default for DeepSeekInference {
     fn default() -> Self {
-        let config = match ProjectConfig::load() {
-            Ok(config) => config,
-            Err(_) => ProjectConfig::default(),
-        };
-        
-        DeepSeekInference {
-            model: config.model,
-            client: Client::new(),
-            base_url: config.base_url,
-            api_key: config.api_key,
-            max_output_tokens: config.max_output_tokens,
-        }
+        Self::from_config(&ProjectConfig::load().unwrap_or_default())
     }
 }
 
 impl DeepSeekInference {
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    fn get_tools(&self) -> Vec<OpenAITool> {
-        vec![
-            self.read_file_tool(),
-            self.write_file_tool(),
-            self.execute_tool(),
-            self.compile_check_tool(),
-        ]
-    }
-
-    fn read_file_tool(&self) -> OpenAITool {
-        OpenAITool {
-            name: "read_file".to_string(),
-            description: "Read file as string using path relative to root directory of project.".to_string(),
-            tool_type: "function".to_string(),
-            function: OpenAIToolFunction {
-                name: "read_file".to_string(),
-                description: "Read file as string using path relative to root directory of project.".to_string(),
-                parameters: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: {
-                        let mut map = HashMap::new();
-                        map.insert(
-                            "path".to_string(),
-                            PropertySchema {
-                                property_type: "string".to_string(),
-                                description: "The file path relative to the project root directory".to_string(),
-                            },
-                        );
-                        map
-                    },
-                    required: vec!["path".to_string()],
-                },
-            },
-        }
-    }
-
-    fn write_file_tool(&self) -> OpenAITool {
-        OpenAITool {
-            name: "write_file".to_string(),
-            description: "Write string to file at path relative to root directory of project.".to_string(),
-            tool_type: "function".to_string(),
-            function: OpenAIToolFunction {
-                name: "write_file".to_string(),
-                description: "Write string to file at path relative to root directory of project.".to_string(),
-                parameters: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: {
-                        let mut map = HashMap::new();
-                        map.insert(
-                            "path".to_string(),
-                            PropertySchema {
-                                property_type: "string".to_string(),
-                                description: "The file path relative to the project root directory".to_string(),
-                            },
-                        );
-                        map.insert(
-                            "content".to_string(),
-                            PropertySchema {
-                                property_type: "string".to_string(),
-                                description: "The content to write to the file".to_string(),
-                            },
-                        );
-                        map
-                    },
-                    required: vec!["path".to_string(), "content".to_string()],
-                },
-            },
-        }
-    }
-
-    fn execute_tool(&self) -> OpenAITool {
-        OpenAITool {
-            name: "execute".to_string(),
-            description: "Execute bash statements as a single string..".to_string(),
-            tool_type: "function".to_string(),
-            function: OpenAIToolFunction {
-                name: "execute".to_string(),
-                description: "Execute bash statements as a single string..".to_string(),
-                parameters: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: {
-                        let mut map = HashMap::new();
-                        map.insert(
-                            "statement".to_string(),
-                            PropertySchema {
-                                property_type: "string".to_string(),
-                                description: "The bash statement to be executed.".to_string(),
-                            },
-                        );
-                        map
-                    },
-                    required: vec!["statement".to_string()],
-                },
-            },
+    pub fn from_config(config: &ProjectConfig) -> Self {
+        DeepSeekInference {
+            model: config.model.clone(),
+            client: http::client(&config.timeouts),
+            read_timeout: config.timeouts.read(),
+            base_url: config.base_url.clone(),
+            api_key: config.api_key.clone(),
+            max_output_tokens: config.max_output_tokens,
+            sampling: config.sampling.clone(),
+            tools: ToolRegistry::default(),
         }
     }
 
-    fn compile_check_tool(&self) -> OpenAITool {
-        OpenAITool {
-            name: "compile_check".to_string(),
-            description: "Check if project compiles or runs without error.".to_string(),
-            tool_type: "function".to_string(),
-            function: OpenAIToolFunction {
-                name: "compile_check".to_string(),
-                description: "Check if project compiles or runs without error.".to_string(),
-                parameters: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: {
-                        let mut map = HashMap::new();
-                        map.insert(
-                            "cmd".to_string(),
-                            PropertySchema {
-                                property_type: "string".to_string(),
-                                description: "The command to check for compiler/interpreter errors.".to_string(),
-                            },
-                        );
-                        map
-                    },
-                    required: vec!["cmd".to_string()],
-                },
-            },
-        }
+    fn get_tools(&self) -> Vec<OpenAITool> {
+        self.tools.openai_tools()
     }
 
     fn get_tools_json(&self) -> Result<serde_json::Value, serde_json::Error> {
         serde_json::to_value(self.get_tools())
     }
+}
 
-    pub async fn query_model(&self, mut messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, InferenceError> {
+#[async_trait]
+impl Inference for DeepSeekInference {
+    async fn query_model(&self, mut messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, InferenceError> {
         if self.api_key.is_empty() {
             return Err(InferenceError::MissingApiKey("DeepSeek API key not found".to_string()));
         }
+        self.sampling.validate("deepseek", &["temperature", "top_p", "stop_sequences", "tool_choice"])?;
 
         if let Some(sys_msg) = system_message {
             messages.insert(0, Message {
                 role: Role::System,
                 content: vec![ContentItem::Text { text: sys_msg.to_string() }],
+                model: None,
             });
         }
 
-        let deepseek_messages = messages.into_iter().map(|msg| {
-            let content = msg.content.iter()
-                .filter_map(|item| {
-                    match item {
-                        ContentItem::Text { text } => Some(text.clone()),
-                        _ => None
-                    }
-                })
-                .collect::<Vec<String>>()
-                .join(" ");
-
-            serde_json::json!({
-                "role": match msg.role {
-                    Role::User => "user",
-                    Role::Assistant => "assistant",
-                    Role::System => "system",
-                    Role::Developer => "developer",
-                },
-                "content": content
-            })
-        }).collect();
+        let deepseek_messages = to_openai_messages(messages);
 
         let tools = self.get_tools_json()
             .map_err(|e| InferenceError::SerializationError(e.to_string())).ok();
@@ -273,6 +183,10 @@ impl DeepSeekInference {
             messages: deepseek_messages,
             max_tokens: Some(self.max_output_tokens),
             tools,
+            temperature: self.sampling.temperature,
+            top_p: self.sampling.top_p,
+            stop: self.sampling.stop_sequences.clone(),
+            tool_choice: self.sampling.tool_choice.as_ref().map(openai_tool_choice),
         };
 
         let response = self.client
@@ -282,15 +196,15 @@ impl DeepSeekInference {
             .json(&request)
             .send()
             .await
-            .map_err(|e| InferenceError::NetworkError(e.to_string()))?;
+            .map_err(InferenceError::from)?;
 
         let status = response.status();
-        let response_text = response.text().await
-            .map_err(|e| InferenceError::NetworkError(e.to_string()))?;
+        let headers = response.headers().clone();
+        let response_text = http::read_text(response, self.read_timeout).await?;
         log::info!("{:?}", response_text);
 
         if !status.is_success() {
-            return Err(InferenceError::ApiError(status, response_text));
+            return Err(InferenceError::from_response(status, &headers, response_text));
         }
 
         let deepseek_response: DeepSeekResponse = serde_json::from_str(&response_text)
@@ -303,6 +217,15 @@ impl DeepSeekInference {
         let first_choice = &deepseek_response.choices[0].message;
         let mut content = first_choice.content.clone();
 
+        // deepseek-reasoner returns its reasoning next to the answer.  It is kept for the
+        // client but never sent back, DeepSeek rejects reasoning_content in requests.
+        if let Some(reasoning) = first_choice.reasoning_content.as_ref().filter(|r| !r.is_empty()) {
+            content.insert(0, ContentItem::Thinking {
+                thinking: reasoning.clone(),
+                signature: String::new(),
+            });
+        }
+
         // Handle tool calls if present
         if let Some(tool_calls) = &first_choice.tool_calls {
             for tool_call in tool_calls {
@@ -328,12 +251,23 @@ impl DeepSeekInference {
             message_type: "text".to_string(),
             stop_reason: deepseek_response.choices[0].finish_reason.clone(),
             stop_sequence: None,
-            //usage: Some(Usage {
-            //    input_tokens: deepseek_response.usage.prompt_tokens,
-            //    output_tokens: deepseek_response.usage.completion_tokens,
-            //    cache_creation_input_tokens: deepseek_response.usage.prompt_cache_miss_tokens,
-            //    cache_read_input_tokens: deepseek_response.usage.prompt_cache_hit_tokens,
-            //}),
+            usage: deepseek_response.usage.map(Usage::from).unwrap_or_default(),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_without_cache_counters() {
+        let usage: DeepSeekUsage = serde_json::from_str(r#"{"prompt_tokens": 120, "completion_tokens": 30, "total_tokens": 150}"#).unwrap();
+        let usage = Usage::from(usage);
+        assert_eq!((usage.input_tokens, usage.output_tokens, usage.cache_read_input_tokens), (120, 30, 0));
+
+        let usage: DeepSeekUsage = serde_json::from_str(r#"{"prompt_tokens": 120, "completion_tokens": 30, "prompt_cache_hit_tokens": 100, "prompt_cache_miss_tokens": 20}"#).unwrap();
+        let usage = Usage::from(usage);
+        assert_eq!((usage.input_tokens, usage.cache_read_input_tokens), (20, 100));
+    }
+}
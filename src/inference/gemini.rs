@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use anyhow::Result;
+
+use crate::config::{ProjectConfig, SamplingConfig, ToolChoice};
+use crate::tools::ToolRegistry;
+use super::http;
+use super::types::{
+    ContentItem, Inference, InferenceError, Message, ModelResponse, Role, Usage
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+    #[serde(default)]
+    usage_metadata: Option<GeminiUsage>,
+    #[serde(default)]
+    model_version: Option<String>,
+    #[serde(default)]
+    response_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCandidate {
+    #[serde(default)]
+    content: Option<GeminiContent>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiContent {
+    #[serde(default)]
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiPart {
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    function_call: Option<GeminiFunctionCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiFunctionCall {
+    #[serde(default)]
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUsage {
+    #[serde(default)]
+    prompt_token_count: u64,
+    #[serde(default)]
+    candidates_token_count: u64,
+    #[serde(default)]
+    cached_content_token_count: u64,
+}
+
+pub struct GeminiInference {
+    model: String,
+    client: Client,
+    read_timeout: Duration,
+    base_url: String,
+    api_key: String,
+    max_output_tokens: u32,
+    sampling: SamplingConfig,
+    tools: ToolRegistry,
+}
+
+impl std::default::Default for GeminiInference {
+    fn default() -> Self {
+        Self::from_config(&ProjectConfig::load().unwrap_or_default())
+    }
+}
+
+impl GeminiInference {
+    pub const DEFAULT_BASE_URL: &'static str = "https://generativelanguage.googleapis.com/v1beta";
+
+    pub fn from_config(config: &ProjectConfig) -> Self {
+        GeminiInference {
+            model: config.model.clone(),
+            client: http::client(&config.timeouts),
+            read_timeout: config.timeouts.read(),
+            base_url: match config.base_url.trim_end_matches('/') {
+                "" => Self::DEFAULT_BASE_URL.to_string(),
+                base_url => base_url.to_string(),
+            },
+            api_key: config.api_key.clone(),
+            max_output_tokens: config.max_output_tokens,
+            sampling: config.sampling.clone(),
+            tools: ToolRegistry::default(),
+        }
+    }
+
+    fn function_declarations(&self) -> Vec<serde_json::Value> {
+        self.tools.iter()
+            .map(|tool| json!({
+                "name": tool.name(),
+                "description": tool.description(),
+                "parameters": tool.input_schema(),
+            }))
+            .collect()
+    }
+
+    fn generation_config(&self) -> serde_json::Value {
+        let mut config = json!({ "maxOutputTokens": self.max_output_tokens });
+        if let Some(temperature) = self.sampling.temperature {
+            config["temperature"] = json!(temperature);
+        }
+        if let Some(top_p) = self.sampling.top_p {
+            config["topP"] = json!(top_p);
+        }
+        if let Some(top_k) = self.sampling.top_k {
+            config["topK"] = json!(top_k);
+        }
+        if !self.sampling.stop_sequences.is_empty() {
+            config["stopSequences"] = json!(self.sampling.stop_sequences);
+        }
+        if let Some(seed) = self.sampling.seed {
+            config["seed"] = json!(seed);
+        }
+        config
+    }
+
+    fn build_request(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<serde_json::Value, InferenceError> {
+        self.sampling.validate("gemini", &["temperature", "top_p", "top_k", "stop_sequences", "seed", "tool_choice"])?;
+
+        let mut request = json!({
+            "contents": to_gemini_contents(messages),
+            "tools": [{ "functionDeclarations": self.function_declarations() }],
+            "generationConfig": self.generation_config(),
+        });
+        if let Some(sys_msg) = system_message {
+            request["systemInstruction"] = json!({ "parts": [{ "text": sys_msg }] });
+        }
+        if let Some(choice) = &self.sampling.tool_choice {
+            request["toolConfig"] = json!({ "functionCallingConfig": match choice {
+                ToolChoice::Auto => json!({ "mode": "AUTO" }),
+                ToolChoice::Any => json!({ "mode": "ANY" }),
+                ToolChoice::None => json!({ "mode": "NONE" }),
+                ToolChoice::Tool(name) => json!({ "mode": "ANY", "allowedFunctionNames": [name] }),
+            }});
+        }
+        Ok(request)
+    }
+}
+
+#[async_trait]
+impl Inference for GeminiInference {
+    async fn query_model(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, InferenceError> {
+        if self.api_key.is_empty() {
+            return Err(InferenceError::MissingApiKey("Gemini API key not found".to_string()));
+        }
+
+        let request = self.build_request(messages, system_message)?;
+
+        let response = self.client
+            .post(format!("{}/models/{}:generateContent", self.base_url, self.model))
+            .header("Content-Type", "application/json")
+            .header("x-goog-api-key", &self.api_key)
+            .json(&request)
+            .send()
+            .await
+            .map_err(InferenceError::from)?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let response_text = http::read_text(response, self.read_timeout).await?;
+        log::info!("{}", response_text);
+
+        if !status.is_success() {
+            return Err(InferenceError::from_response(status, &headers, response_text));
+        }
+
+        parse_response(&response_text, &self.model)
+    }
+}
+
+fn unique_suffix() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos()
+}
+
+/// Translates chat history into Gemini `contents`.  Gemini pairs a `functionResponse` with
+/// its `functionCall` by name, so the name of every tool use is remembered for its result.
+fn to_gemini_contents(messages: Vec<Message>) -> Vec<serde_json::Value> {
+    let mut tool_names = HashMap::new();
+    let mut contents = Vec::new();
+
+    for msg in messages {
+        let role = match msg.role {
+            Role::Assistant => "model",
+            _ => "user",
+        };
+
+        let parts = msg.content.into_iter()
+            .filter_map(|item| match item {
+                ContentItem::Text { text } => Some(json!({ "text": text })),
+                ContentItem::ToolUse { id, name, input } => {
+                    tool_names.insert(id.clone(), name.clone());
+                    Some(json!({ "functionCall": { "id": id, "name": name, "args": input } }))
+                },
+                ContentItem::ToolResult { tool_use_id, content } => {
+                    let name = tool_names.get(&tool_use_id).cloned().unwrap_or_default();
+                    Some(json!({
+                        "functionResponse": {
+                            "id": tool_use_id,
+                            "name": name,
+                            "response": { "content": content },
+                        }
+                    }))
+                },
+                ContentItem::Image { source } => source.base64().map(|(media_type, data)| json!({
+                    "inlineData": { "mimeType": media_type, "data": data }
+                })),
+                // Reasoning from other providers means nothing to Gemini
+                ContentItem::Thinking { .. } | ContentItem::RedactedThinking { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        if !parts.is_empty() {
+            contents.push(json!({ "role": role, "parts": parts }));
+        }
+    }
+
+    contents
+}
+
+fn parse_response(response_text: &str, model: &str) -> Result<ModelResponse, InferenceError> {
+    let gemini_response: GeminiResponse = serde_json::from_str(response_text)
+        .map_err(|e| InferenceError::InvalidResponse(format!("Failed to parse Gemini response: {}", e)))?;
+
+    let candidate = gemini_response.candidates.into_iter().next()
+        .ok_or_else(|| InferenceError::InvalidResponse("No candidates in Gemini response".to_string()))?;
+
+    let mut content = Vec::new();
+    for (i, part) in candidate.content.map(|c| c.parts).unwrap_or_default().into_iter().enumerate() {
+        if let Some(text) = part.text {
+            content.push(ContentItem::Text { text });
+        }
+        if let Some(call) = part.function_call {
+            content.push(ContentItem::ToolUse {
+                id: call.id.unwrap_or_else(|| format!("call_{}_{}", unique_suffix(), i)),
+                name: call.name,
+                input: call.args,
+            });
+        }
+    }
+
+    // Gemini reports STOP even when it calls functions
+    let stop_reason = if content.iter().any(|item| matches!(item, ContentItem::ToolUse { .. })) {
+        "tool_use".to_string()
+    } else {
+        candidate.finish_reason.unwrap_or_default()
+    };
+
+    let usage = gemini_response.usage_metadata
+        .map(|usage| Usage {
+            input_tokens: usage.prompt_token_count.saturating_sub(usage.cached_content_token_count),
+            output_tokens: usage.candidates_token_count,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: usage.cached_content_token_count,
+        })
+        .unwrap_or_default();
+
+    Ok(ModelResponse {
+        content,
+        id: gemini_response.response_id.unwrap_or_else(|| format!("gemini_{}", unique_suffix())),
+        model: gemini_response.model_version.unwrap_or_else(|| model.to_string()),
+        role: "assistant".to_string(),
+        message_type: "text".to_string(),
+        stop_reason,
+        stop_sequence: None,
+        usage,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::stub_server::{self, StubResponse};
+
+    #[tokio::test]
+    async fn test_query_model_maps_function_calls() -> Result<(), InferenceError> {
+        let (base_url, requests) = stub_server::spawn(vec![StubResponse::json(r#"{
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        {"text": "I'll check the compile."},
+                        {"functionCall": {"name": "compile_check", "args": {"cmd": "cargo check"}}}
+                    ]
+                },
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {"promptTokenCount": 1200, "candidatesTokenCount": 25, "cachedContentTokenCount": 1000, "totalTokenCount": 1225},
+            "modelVersion": "gemini-2.0-flash",
+            "responseId": "resp_123"
+        }"#)]).await;
+
+        let config = ProjectConfig {
+            provider: "gemini".to_string(),
+            model: "gemini-2.0-flash".to_string(),
+            base_url,
+            api_key: "test-key".to_string(),
+            ..ProjectConfig::default()
+        };
+        let history = vec![
+            Message {
+                role: Role::User,
+                content: vec![ContentItem::Text { text: "Read main.rs".to_string() }],
+                model: None,
+            },
+            Message {
+                role: Role::Assistant,
+                content: vec![ContentItem::ToolUse {
+                    id: "call_1".to_string(),
+                    name: "read_file".to_string(),
+                    input: json!({"path": "src/main.rs"}),
+                }],
+                model: None,
+            },
+            Message {
+                role: Role::User,
+                content: vec![ContentItem::ToolResult {
+                    tool_use_id: "call_1".to_string(),
+                    content: "fn main() {}".to_string(),
+                }],
+                model: None,
+            },
+        ];
+
+        let response = GeminiInference::from_config(&config).query_model(history, Some("Be brief.")).await?;
+
+        assert_eq!(response.id, "resp_123");
+        assert_eq!(response.stop_reason, "tool_use");
+        assert_eq!(response.usage.input_tokens, 200);
+        assert_eq!(response.usage.cache_read_input_tokens, 1000);
+        assert_eq!(response.content[0], ContentItem::Text { text: "I'll check the compile.".to_string() });
+        assert!(matches!(&response.content[1], ContentItem::ToolUse { name, input, .. }
+            if name == "compile_check" && input["cmd"] == "cargo check"));
+
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.request_line.starts_with("POST /models/gemini-2.0-flash:generateContent "));
+        assert_eq!(request.header("x-goog-api-key"), Some("test-key"));
+
+        let body = request.json();
+        assert_eq!(body["systemInstruction"]["parts"][0]["text"], "Be brief.");
+        let declarations = body["tools"][0]["functionDeclarations"].as_array().unwrap();
+        assert!(declarations.iter().any(|d| d["name"] == "read_file" && d["parameters"]["required"] == json!(["path"])));
+        assert_eq!(body["contents"][1]["role"], "model");
+        assert_eq!(body["contents"][1]["parts"][0]["functionCall"]["args"]["path"], "src/main.rs");
+        assert_eq!(body["contents"][2]["parts"][0]["functionResponse"], json!({
+            "id": "call_1",
+            "name": "read_file",
+            "response": { "content": "fn main() {}" },
+        }));
+        Ok(())
+    }
+}
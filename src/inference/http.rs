@@ -0,0 +1,89 @@
+use std::pin::Pin;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::{stream, Stream, StreamExt};
+use reqwest::Client;
+
+use crate::config::TimeoutConfig;
+use super::types::InferenceError;
+
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, InferenceError>> + Send>>;
+
+/// HTTP client shared by the providers.  Connecting and the whole call, streamed body
+/// included, are bounded by the `[timeouts]` config.
+pub fn client(timeouts: &TimeoutConfig) -> Client {
+    Client::builder()
+        .connect_timeout(timeouts.connect())
+        .timeout(timeouts.request())
+        .build()
+        .expect("Failed to build HTTP client.")
+}
+
+/// The response body, failing with `InferenceError::Timeout` when no bytes arrive for
+/// `read_timeout`.  Waiting for the headers isn't bounded by it since non-streamed
+/// responses only start once the model is done.
+pub fn body_stream(response: reqwest::Response, read_timeout: Duration) -> ByteStream {
+    let bytes = Box::pin(response.bytes_stream());
+    Box::pin(stream::unfold(Some(bytes), move |bytes| async move {
+        let mut bytes = bytes?;
+        match tokio::time::timeout(read_timeout, bytes.next()).await {
+            Ok(Some(Ok(chunk))) => Some((Ok(chunk), Some(bytes))),
+            Ok(Some(Err(e))) => Some((Err(InferenceError::from(e)), None)),
+            Ok(None) => None,
+            Err(_) => Some((Err(InferenceError::Timeout(
+                format!("No response data for {}s", read_timeout.as_secs())
+            )), None)),
+        }
+    }))
+}
+
+/// Reads the whole response body as text, with the same idle timeout as `body_stream`.
+pub async fn read_text(response: reqwest::Response, read_timeout: Duration) -> Result<String, InferenceError> {
+    let mut body = Vec::new();
+    let mut bytes = body_stream(response, read_timeout);
+    while let Some(chunk) = bytes.next().await {
+        body.extend_from_slice(&chunk?);
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_stalled_body_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 100\r\n\r\n{\"partial\":").await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let response = client(&TimeoutConfig::default())
+            .get(format!("http://{}", addr))
+            .send()
+            .await
+            .unwrap();
+        let error = read_text(response, Duration::from_millis(200)).await.unwrap_err();
+        assert!(matches!(error, InferenceError::Timeout(_)), "{:?}", error);
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_is_reported_as_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let timeouts = TimeoutConfig { request_secs: 1, ..TimeoutConfig::default() };
+        let error = client(&timeouts).get(format!("http://{}", addr)).send().await.unwrap_err();
+        assert!(matches!(InferenceError::from(error), InferenceError::Timeout(_)));
+    }
+}
@@ -2,11 +2,24 @@ pub mod anthropic;
 pub mod openai;
 pub mod deepseek;
 pub mod bedrock;
+pub mod ollama;
+pub mod gemini;
+pub mod http;
+pub mod registry;
+pub mod replay;
+pub mod retry;
+pub mod sse;
+pub mod tokens;
 pub mod tools;
 pub mod types;
+#[cfg(test)]
+pub mod stub_server;
 
 // Re-export the inference types
 pub use anthropic::AnthropicInference;
 pub use openai::OpenAIInference;
 pub use deepseek::DeepSeekInference;
-pub use bedrock::AWSBedrockInference;
\ No newline at end of file
+pub use bedrock::AWSBedrockInference;
+pub use ollama::OllamaInference;
+pub use gemini::GeminiInference;
+pub use replay::ReplayInference;
\ No newline at end of file
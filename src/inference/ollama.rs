@@ -0,0 +1,531 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+use anyhow::Result;
+
+use crate::config::{OllamaConfig, ProjectConfig, SamplingConfig};
+use crate::tools::ToolRegistry;
+use super::http;
+use super::types::{
+    ContentItem, EventStream, Inference, InferenceError, Message, ModelResponse, Role, StreamEvent, Usage
+};
+use super::tools::OpenAITool;
+
+#[derive(Serialize)]
+struct OllamaRequest<'a> {
+    model: &'a str,
+    messages: Vec<serde_json::Value>,
+    tools: Vec<OpenAITool>,
+    stream: bool,
+    options: OllamaOptions,
+}
+
+#[derive(Serialize)]
+struct OllamaOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_ctx: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    num_predict: u32,
+}
+
+/// One line of an `/api/chat` response.  Non-streaming responses are a single chunk with
+/// `done: true`.
+#[derive(Debug, Deserialize)]
+struct OllamaChunk {
+    /// Error lines (`{"error": "..."}`) have none of the other fields
+    #[serde(default)]
+    model: String,
+    #[serde(default)]
+    message: Option<OllamaMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    done_reason: Option<String>,
+    #[serde(default)]
+    prompt_eval_count: u64,
+    #[serde(default)]
+    eval_count: u64,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaMessage {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    tool_calls: Vec<OllamaToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaToolCall {
+    function: OllamaFunctionCall,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaFunctionCall {
+    name: String,
+    arguments: serde_json::Value,
+}
+
+pub struct OllamaInference {
+    model: String,
+    client: Client,
+    read_timeout: Duration,
+    base_url: String,
+    max_output_tokens: u32,
+    options: OllamaConfig,
+    sampling: SamplingConfig,
+    tools: ToolRegistry,
+}
+
+impl std::default::Default for OllamaInference {
+    fn default() -> Self {
+        Self::from_config(&ProjectConfig::load().unwrap_or_default())
+    }
+}
+
+impl OllamaInference {
+    pub fn from_config(config: &ProjectConfig) -> Self {
+        OllamaInference {
+            model: config.model.clone(),
+            client: http::client(&config.timeouts),
+            read_timeout: config.timeouts.read(),
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            max_output_tokens: config.max_output_tokens,
+            options: config.ollama.clone(),
+            sampling: config.sampling.clone(),
+            tools: ToolRegistry::default(),
+        }
+    }
+
+    fn build_request(&self, mut messages: Vec<Message>, system_message: Option<&str>, stream: bool) -> Result<OllamaRequest<'_>, InferenceError> {
+        self.sampling.validate("ollama", &["temperature", "top_p", "top_k", "stop_sequences", "seed"])?;
+        if let Some(sys_msg) = system_message {
+            messages.insert(0, Message {
+                role: Role::System,
+                content: vec![ContentItem::Text { text: sys_msg.to_string() }],
+                model: None,
+            });
+        }
+
+        Ok(OllamaRequest {
+            model: &self.model,
+            messages: to_ollama_messages(messages),
+            tools: self.tools.openai_tools(),
+            stream,
+            options: OllamaOptions {
+                num_ctx: self.options.num_ctx,
+                temperature: self.sampling.temperature,
+                top_p: self.sampling.top_p,
+                top_k: self.sampling.top_k,
+                stop: self.sampling.stop_sequences.clone(),
+                seed: self.sampling.seed,
+                num_predict: self.max_output_tokens,
+            },
+        })
+    }
+
+    async fn send(&self, request: &OllamaRequest<'_>) -> Result<reqwest::Response, InferenceError> {
+        let response = self.client
+            .post(format!("{}/api/chat", self.base_url))
+            .json(request)
+            .send()
+            .await
+            .map_err(InferenceError::from)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let headers = response.headers().clone();
+            let response_text = http::read_text(response, self.read_timeout).await?;
+            log::info!("{}", response_text);
+            return Err(InferenceError::from_response(status, &headers, response_text));
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl Inference for OllamaInference {
+    async fn query_model(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, InferenceError> {
+        let request = self.build_request(messages, system_message, false)?;
+        let response = self.send(&request).await?;
+
+        let response_text = http::read_text(response, self.read_timeout).await?;
+        log::info!("{}", response_text);
+
+        let chunk: OllamaChunk = serde_json::from_str(&response_text)
+            .map_err(|e| InferenceError::InvalidResponse(format!("Failed to parse Ollama response: {}", e)))?;
+        let mut accumulator = ChunkAccumulator::default();
+        accumulator.handle(chunk)?;
+        Ok(accumulator.finish())
+    }
+
+    /// Streams `/api/chat`, which sends one JSON object per line rather than SSE.
+    async fn query_model_stream(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<EventStream, InferenceError> {
+        let request = self.build_request(messages, system_message, true)?;
+        let response = self.send(&request).await?;
+
+        let state = StreamState {
+            bytes: http::body_stream(response, self.read_timeout),
+            buffer: Vec::new(),
+            accumulator: ChunkAccumulator::default(),
+            pending: VecDeque::new(),
+            finished: false,
+        };
+
+        let stream = stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(event) = state.pending.pop_front() {
+                    return Some((event, state));
+                }
+                if state.finished {
+                    return None;
+                }
+
+                match state.bytes.next().await {
+                    Some(Ok(chunk)) => {
+                        state.buffer.extend_from_slice(&chunk);
+                        while let Some(pos) = state.buffer.iter().position(|b| *b == b'\n') {
+                            let line: Vec<u8> = state.buffer.drain(..=pos).collect();
+                            if line.iter().all(|b| b.is_ascii_whitespace()) {
+                                continue;
+                            }
+                            let events = serde_json::from_slice::<OllamaChunk>(&line)
+                                .map_err(|e| InferenceError::InvalidResponse(format!("Invalid stream chunk: {}", e)))
+                                .and_then(|chunk| state.accumulator.handle(chunk));
+                            match events {
+                                Ok(events) => {
+                                    state.finished |= events.iter().any(|e| matches!(e, StreamEvent::Done { .. }));
+                                    state.pending.extend(events.into_iter().map(Ok));
+                                },
+                                Err(e) => {
+                                    state.pending.push_back(Err(e));
+                                    state.finished = true;
+                                    break;
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => {
+                        state.pending.push_back(Err(e));
+                        state.finished = true;
+                    },
+                    None => {
+                        state.pending.push_back(Err(InferenceError::InvalidResponse(
+                            "Stream ended before the final chunk".to_string()
+                        )));
+                        state.finished = true;
+                    }
+                }
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+struct StreamState {
+    bytes: http::ByteStream,
+    buffer: Vec<u8>,
+    accumulator: ChunkAccumulator,
+    pending: VecDeque<Result<StreamEvent, InferenceError>>,
+    finished: bool,
+}
+
+#[derive(Default)]
+struct ChunkAccumulator {
+    model: String,
+    text: String,
+    tool_uses: Vec<ContentItem>,
+    stop_reason: String,
+    usage: Usage,
+}
+
+impl ChunkAccumulator {
+    fn handle(&mut self, chunk: OllamaChunk) -> Result<Vec<StreamEvent>, InferenceError> {
+        if let Some(error) = chunk.error {
+            return Err(InferenceError::InvalidResponse(error));
+        }
+
+        let mut events = Vec::new();
+        self.model = chunk.model;
+
+        if let Some(message) = chunk.message {
+            if !message.content.is_empty() {
+                self.text.push_str(&message.content);
+                events.push(StreamEvent::TextDelta { text: message.content });
+            }
+            // Ollama doesn't assign ids to tool calls, but the chat protocol needs them to
+            // pair each result with its call
+            for tool_call in message.tool_calls {
+                let id = format!("call_{}_{}", unique_suffix(), self.tool_uses.len());
+                let tool_use = ContentItem::ToolUse {
+                    id: id.clone(),
+                    name: tool_call.function.name.clone(),
+                    input: tool_call.function.arguments,
+                };
+                events.push(StreamEvent::ToolUseStart { id, name: tool_call.function.name });
+                events.push(StreamEvent::ToolUse { tool_use: tool_use.clone() });
+                self.tool_uses.push(tool_use);
+            }
+        }
+
+        if chunk.done {
+            self.stop_reason = chunk.done_reason.unwrap_or_else(|| "stop".to_string());
+            self.usage = Usage {
+                input_tokens: chunk.prompt_eval_count,
+                output_tokens: chunk.eval_count,
+                ..Usage::default()
+            };
+            events.push(StreamEvent::Done { response: self.finish() });
+        }
+
+        Ok(events)
+    }
+
+    fn finish(&self) -> ModelResponse {
+        let mut content = Vec::new();
+        if !self.text.is_empty() {
+            content.push(ContentItem::Text { text: self.text.clone() });
+        }
+        content.extend(self.tool_uses.iter().cloned());
+
+        let stop_reason = if self.tool_uses.is_empty() { self.stop_reason.clone() } else { "tool_use".to_string() };
+
+        ModelResponse {
+            content,
+            id: format!("ollama_{}", unique_suffix()),
+            model: self.model.clone(),
+            role: "assistant".to_string(),
+            message_type: "text".to_string(),
+            stop_reason,
+            stop_sequence: None,
+            usage: self.usage,
+        }
+    }
+}
+
+fn unique_suffix() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos()
+}
+
+/// Translates chat history into Ollama messages.  Tool calls carry their arguments as an
+/// object and tool results are matched to their call by name, since Ollama has no ids.
+fn to_ollama_messages(messages: Vec<Message>) -> Vec<serde_json::Value> {
+    let mut tool_names = HashMap::new();
+    let mut ollama_messages = Vec::new();
+
+    for msg in messages {
+        let role = match msg.role {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::System | Role::Developer => "system",
+        };
+
+        let mut text = Vec::new();
+        let mut tool_calls = Vec::new();
+        let mut images = Vec::new();
+        for item in msg.content {
+            match item {
+                ContentItem::Text { text: t } => text.push(t),
+                ContentItem::ToolUse { id, name, input } => {
+                    tool_calls.push(serde_json::json!({
+                        "function": { "name": name, "arguments": input }
+                    }));
+                    tool_names.insert(id, name);
+                },
+                ContentItem::ToolResult { tool_use_id, content } => {
+                    ollama_messages.push(serde_json::json!({
+                        "role": "tool",
+                        "tool_name": tool_names.get(&tool_use_id).cloned().unwrap_or_default(),
+                        "content": content,
+                    }));
+                },
+                ContentItem::Image { source } => {
+                    if let Some((_, data)) = source.base64() {
+                        images.push(serde_json::Value::String(data.to_string()));
+                    }
+                },
+                ContentItem::Thinking { .. } | ContentItem::RedactedThinking { .. } => {},
+            }
+        }
+
+        if !text.is_empty() || !tool_calls.is_empty() || !images.is_empty() {
+            let mut message = serde_json::json!({
+                "role": role,
+                "content": text.join(" "),
+            });
+            if !tool_calls.is_empty() {
+                message["tool_calls"] = serde_json::Value::Array(tool_calls);
+            }
+            if !images.is_empty() {
+                message["images"] = serde_json::Value::Array(images);
+            }
+            ollama_messages.push(message);
+        }
+    }
+
+    ollama_messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::stub_server::{self, StubResponse};
+
+    fn test_config(base_url: &str) -> ProjectConfig {
+        ProjectConfig {
+            provider: "ollama".to_string(),
+            model: "qwen2.5-coder:7b".to_string(),
+            base_url: base_url.to_string(),
+            ollama: OllamaConfig { num_ctx: Some(32768) },
+            sampling: SamplingConfig { temperature: Some(0.1), ..SamplingConfig::default() },
+            ..ProjectConfig::default()
+        }
+    }
+
+    fn history() -> Vec<Message> {
+        vec![
+            Message {
+                role: Role::User,
+                content: vec![ContentItem::Text { text: "What is in main.rs?".to_string() }],
+                model: None,
+            },
+            Message {
+                role: Role::Assistant,
+                content: vec![ContentItem::ToolUse {
+                    id: "call_1".to_string(),
+                    name: "read_file".to_string(),
+                    input: serde_json::json!({"path": "src/main.rs"}),
+                }],
+                model: None,
+            },
+            Message {
+                role: Role::User,
+                content: vec![ContentItem::ToolResult {
+                    tool_use_id: "call_1".to_string(),
+                    content: "fn main() {}".to_string(),
+                }],
+                model: None,
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_query_model_sends_native_request() -> Result<(), InferenceError> {
+        let (base_url, requests) = stub_server::spawn(vec![StubResponse::json(r#"{
+            "model": "qwen2.5-coder:7b",
+            "created_at": "2025-01-10T12:00:00Z",
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [{"function": {"name": "write_file", "arguments": {"path": "src/main.rs", "content": "fn main() { println!(\"hi\"); }"}}}]
+            },
+            "done_reason": "stop",
+            "done": true,
+            "prompt_eval_count": 412,
+            "eval_count": 38
+        }"#)]).await;
+
+        let inference = OllamaInference::from_config(&test_config(&base_url));
+        let response = inference.query_model(history(), Some("Be helpful.")).await?;
+
+        assert_eq!(response.stop_reason, "tool_use");
+        assert_eq!(response.usage.input_tokens, 412);
+        assert_eq!(response.usage.output_tokens, 38);
+        match &response.content[..] {
+            [ContentItem::ToolUse { name, input, .. }] => {
+                assert_eq!(name, "write_file");
+                assert_eq!(input["path"], "src/main.rs");
+            },
+            other => panic!("Unexpected content {:?}", other),
+        }
+
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.request_line.starts_with("POST /api/chat "));
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        let body = request.json();
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["options"]["num_ctx"], 32768);
+        assert_eq!(body["options"]["temperature"], 0.1);
+        assert_eq!(body["tools"][0]["type"], "function");
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][2]["tool_calls"][0]["function"]["arguments"]["path"], "src/main.rs");
+        assert_eq!(body["messages"][3], serde_json::json!({
+            "role": "tool",
+            "tool_name": "read_file",
+            "content": "fn main() {}",
+        }));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_model_stream_yields_deltas() -> Result<(), InferenceError> {
+        let body = [
+            r#"{"model":"qwen2.5-coder:7b","message":{"role":"assistant","content":"It "},"done":false}"#,
+            r#"{"model":"qwen2.5-coder:7b","message":{"role":"assistant","content":"prints hi."},"done":false}"#,
+            r#"{"model":"qwen2.5-coder:7b","message":{"role":"assistant","content":""},"done_reason":"stop","done":true,"prompt_eval_count":20,"eval_count":4}"#,
+        ].join("\n") + "\n";
+        let (base_url, requests) = stub_server::spawn(vec![StubResponse {
+            status: 200,
+            content_type: "application/x-ndjson",
+            body,
+        }]).await;
+
+        let inference = OllamaInference::from_config(&test_config(&base_url));
+        let events: Vec<StreamEvent> = inference.query_model_stream(history(), None).await?
+            .collect::<Vec<_>>().await
+            .into_iter()
+            .collect::<Result<_, _>>()?;
+
+        assert!(matches!(&events[0], StreamEvent::TextDelta { text } if text == "It "));
+        match events.last() {
+            Some(StreamEvent::Done { response }) => {
+                assert_eq!(response.content, vec![ContentItem::Text { text: "It prints hi.".to_string() }]);
+                assert_eq!(response.stop_reason, "stop");
+            },
+            other => panic!("Expected Done event, got {:?}", other),
+        }
+        assert_eq!(requests.lock().unwrap()[0].json()["stream"], true);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_model_stream_reports_error_lines() -> Result<(), InferenceError> {
+        let body = [
+            r#"{"model":"qwen2.5-coder:7b","message":{"role":"assistant","content":"It "},"done":false}"#,
+            r#"{"error":"model runner has unexpectedly stopped"}"#,
+        ].join("\n") + "\n";
+        let (base_url, _requests) = stub_server::spawn(vec![StubResponse {
+            status: 200,
+            content_type: "application/x-ndjson",
+            body,
+        }]).await;
+
+        let inference = OllamaInference::from_config(&test_config(&base_url));
+        let events = inference.query_model_stream(history(), None).await?
+            .collect::<Vec<_>>().await;
+
+        assert!(matches!(&events[0], Ok(StreamEvent::TextDelta { text }) if text == "It "));
+        match events.last() {
+            Some(Err(InferenceError::InvalidResponse(error))) => assert_eq!(error, "model runner has unexpectedly stopped"),
+            other => panic!("Expected the error line, got {:?}", other),
+        }
+        Ok(())
+    }
+}
@@ -1,13 +1,22 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use reqwest::Client;
 use serde::{Serialize, Deserialize};
 use anyhow::Result;
 
-use crate::config::ProjectConfig;
+use crate::config::{AuthScheme, ProjectConfig, SamplingConfig, ToolChoice};
+use super::http;
 use super::types::{
-    ContentItem, InferenceError, Message, ModelResponse, Role
+    ContentItem, Inference, InferenceError, Message, ModelResponse, Role, Usage
 };
-use super::tools::{OpenAITool, OpenAIToolFunction, InputSchema, PropertySchema};
+use crate::tools::ToolRegistry;
+use super::tools::OpenAITool;
+
+/// Azure OpenAI's latest GA API version
+const AZURE_API_VERSION: &str = "2024-10-21";
 
 #[derive(Serialize)]
 struct OpenAIRequest {
@@ -15,6 +24,16 @@ struct OpenAIRequest {
     messages: Vec<serde_json::Value>,
     max_tokens: Option<u32>,
     tools: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,6 +41,34 @@ struct OpenAIResponse {
     id: String,
     model: String,
     choices: Vec<OpenAIChoice>,
+    #[serde(default)]
+    usage: Option<OpenAIUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIUsage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    #[serde(default)]
+    prompt_tokens_details: Option<OpenAIPromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIPromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: u64,
+}
+
+impl From<OpenAIUsage> for Usage {
+    fn from(usage: OpenAIUsage) -> Self {
+        let cached_tokens = usage.prompt_tokens_details.map(|d| d.cached_tokens).unwrap_or_default();
+        Usage {
+            input_tokens: usage.prompt_tokens.saturating_sub(cached_tokens),
+            output_tokens: usage.completion_tokens,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: cached_tokens,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -76,194 +123,122 @@ struct OpenAIFunctionCall {
 pub struct OpenAIInference {
     model: String,
     client: Client,
+    read_timeout: Duration,
     base_url: String,
     api_key: String,
+    auth_scheme: AuthScheme,
+    query_params: BTreeMap<String, String>,
+    /// Checked when the config is read, an invalid header fails every request
+    extra_headers: Result<HeaderMap, String>,
     max_output_tokens: u32,
+    sampling: SamplingConfig,
+    tools: ToolRegistry,
 }
 
 impl std::default::Default for OpenAIInference {
     fn default() -> Self {
-        let config = match ProjectConfig::load() {
-            Ok(config) => config,
-            Err(_) => ProjectConfig::default(),
-        };
-        
-        OpenAIInference {
-            model: config.model,
-            client: Client::new(),
-            base_url: config.base_url,
-            api_key: config.api_key,
-            max_output_tokens: config.max_output_tokens,
-        }
+        Self::from_config(&ProjectConfig::load().unwrap_or_default())
     }
 }
 
 impl OpenAIInference {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn from_config(config: &ProjectConfig) -> Self {
+        OpenAIInference {
+            model: config.model.clone(),
+            client: http::client(&config.timeouts),
+            read_timeout: config.timeouts.read(),
+            base_url: config.base_url.clone(),
+            api_key: config.api_key.clone(),
+            auth_scheme: config.openai.auth_scheme.unwrap_or(AuthScheme::Bearer),
+            query_params: config.openai.query_params.clone(),
+            extra_headers: header_map(&config.openai.extra_headers),
+            max_output_tokens: config.max_output_tokens,
+            sampling: config.sampling.clone(),
+            tools: ToolRegistry::default(),
+        }
     }
 
-    fn get_tools(&self) -> Vec<OpenAITool> {
-        vec![
-            self.read_file_tool(),
-            self.write_file_tool(),
-            self.execute_tool(),
-            self.compile_check_tool(),
-        ]
+    /// Azure OpenAI, where each deployment has its own URL, the key goes in an `api-key`
+    /// header and every request names the `api-version`.
+    pub fn azure(config: &ProjectConfig) -> Self {
+        let deployment = config.azure.deployment.as_deref().unwrap_or(&config.model);
+        let mut inference = Self::from_config(config);
+        inference.base_url = format!("{}/openai/deployments/{}", config.base_url.trim_end_matches('/'), deployment);
+        inference.auth_scheme = config.openai.auth_scheme.unwrap_or(AuthScheme::ApiKey);
+        inference.query_params.entry("api-version".to_string())
+            .or_insert_with(|| config.azure.api_version.clone().unwrap_or_else(|| AZURE_API_VERSION.to_string()));
+        inference
     }
 
-    fn read_file_tool(&self) -> OpenAITool {
-        OpenAITool {
-            name: "read_file".to_string(),
-            description: "Read file as string using path relative to root directory of project.".to_string(),
-            tool_type: "function".to_string(),
-            function: OpenAIToolFunction {
-                name: "read_file".to_string(),
-                description: "Read file as string using path relative to root directory of project.".to_string(),
-                parameters: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: {
-                        let mut map = HashMap::new();
-                        map.insert(
-                            "path".to_string(),
-                            PropertySchema {
-                                property_type: "string".to_string(),
-                                description: "The file path relative to the project root directory".to_string(),
-                            },
-                        );
-                        map
-                    },
-                    required: vec!["path".to_string()],
-                },
-            },
+    /// POST to `path` with the configured auth, query parameters and extra headers.  Extra
+    /// headers replace the auth header when they name it.
+    fn post(&self, path: &str) -> Result<reqwest::RequestBuilder, InferenceError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        let auth = match self.auth_scheme {
+            AuthScheme::Bearer => Some((AUTHORIZATION, format!("Bearer {}", self.api_key))),
+            AuthScheme::ApiKey => Some((HeaderName::from_static("api-key"), self.api_key.clone())),
+            AuthScheme::None => None,
+        };
+        if let Some((name, value)) = auth {
+            let value = HeaderValue::from_str(&value)
+                .map_err(|_| InferenceError::InvalidConfig("The API key contains characters a header can't hold".to_string()))?;
+            headers.insert(name, value);
         }
-    }
-
-    fn write_file_tool(&self) -> OpenAITool {
-        OpenAITool {
-            name: "write_file".to_string(),
-            description: "Write string to file at path relative to root directory of project.".to_string(),
-            tool_type: "function".to_string(),
-            function: OpenAIToolFunction {
-                name: "write_file".to_string(),
-                description: "Write string to file at path relative to root directory of project.".to_string(),
-                parameters: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: {
-                        let mut map = HashMap::new();
-                        map.insert(
-                            "path".to_string(),
-                            PropertySchema {
-                                property_type: "string".to_string(),
-                                description: "The file path relative to the project root directory".to_string(),
-                            },
-                        );
-                        map.insert(
-                            "content".to_string(),
-                            PropertySchema {
-                                property_type: "string".to_string(),
-                                description: "The content to write to the file".to_string(),
-                            },
-                        );
-                        map
-                    },
-                    required: vec!["path".to_string(), "content".to_string()],
-                },
-            },
+        let extra_headers = self.extra_headers.as_ref()
+            .map_err(|e| InferenceError::InvalidConfig(format!("Invalid [openai] extra_headers: {}", e)))?;
+        for (name, value) in extra_headers {
+            headers.insert(name, value.clone());
         }
-    }
 
-    fn execute_tool(&self) -> OpenAITool {
-        OpenAITool {
-            name: "execute".to_string(),
-            description: "Execute bash statements as a single string..".to_string(),
-            tool_type: "function".to_string(),
-            function: OpenAIToolFunction {
-                name: "execute".to_string(),
-                description: "Execute bash statements as a single string..".to_string(),
-                parameters: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: {
-                        let mut map = HashMap::new();
-                        map.insert(
-                            "statement".to_string(),
-                            PropertySchema {
-                                property_type: "string".to_string(),
-                                description: "The bash statement to be executed.".to_string(),
-                            },
-                        );
-                        map
-                    },
-                    required: vec!["statement".to_string()],
-                },
-            },
-        }
+        Ok(self.client
+            .post(format!("{}{}", self.base_url, path))
+            .query(&self.query_params)
+            .headers(headers))
     }
 
-    fn compile_check_tool(&self) -> OpenAITool {
-        OpenAITool {
-            name: "compile_check".to_string(),
-            description: "Check if project compiles or runs without error.".to_string(),
-            tool_type: "function".to_string(),
-            function: OpenAIToolFunction {
-                name: "compile_check".to_string(),
-                description: "Check if project compiles or runs without error.".to_string(),
-                parameters: InputSchema {
-                    schema_type: "object".to_string(),
-                    properties: {
-                        let mut map = HashMap::new();
-                        map.insert(
-                            "cmd".to_string(),
-                            PropertySchema {
-                                property_type: "string".to_string(),
-                                description: "The command to check for compiler/interpreter errors.".to_string(),
-                            },
-                        );
-                        map
-                    },
-                    required: vec!["cmd".to_string()],
-                },
-            },
-        }
+    fn get_tools(&self) -> Vec<OpenAITool> {
+        self.tools.openai_tools()
     }
 
     fn get_tools_json(&self) -> Result<serde_json::Value, serde_json::Error> {
         serde_json::to_value(self.get_tools())
     }
+}
 
-    pub async fn query_model(&self, mut messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, InferenceError> {
-        if self.api_key.is_empty() {
+/// The configured headers, or what is wrong with the first invalid one.
+fn header_map(headers: &BTreeMap<String, String>) -> Result<HeaderMap, String> {
+    headers.iter()
+        .map(|(name, value)| {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| format!("{:?} is not a valid header name", name))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|_| format!("the value of {} is not a valid header value", name))?;
+            Ok((name, value))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Inference for OpenAIInference {
+    async fn query_model(&self, mut messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, InferenceError> {
+        if self.api_key.is_empty() && self.auth_scheme != AuthScheme::None {
             return Err(InferenceError::MissingApiKey("OpenAI API key not found".to_string()));
         }
+        self.sampling.validate("openai", &["temperature", "top_p", "stop_sequences", "seed", "tool_choice"])?;
+        if self.sampling.stop_sequences.len() > 4 {
+            return Err(InferenceError::InvalidConfig("openai accepts at most 4 stop_sequences".to_string()));
+        }
 
         if let Some(sys_msg) = system_message {
             messages.insert(0, Message {
                 role: Role::System,
                 content: vec![ContentItem::Text { text: sys_msg.to_string() }],
+                model: None,
             });
         }
 
-        let openai_messages = messages.into_iter().map(|msg| {
-            let content = msg.content.iter()
-                .filter_map(|item| {
-                    match item {
-                        ContentItem::Text { text } => Some(text.clone()),
-                        _ => None
-                    }
-                })
-                .collect::<Vec<String>>()
-                .join(" ");
-
-            serde_json::json!({
-                "role": match msg.role {
-                    Role::User => "user",
-                    Role::Assistant => "assistant",
-                    Role::System => "system",
-                    Role::Developer => "developer",
-                },
-                "content": content
-            })
-        }).collect();
+        let openai_messages = to_openai_messages(messages);
 
         let tools = self.get_tools_json()
             .map_err(|e| InferenceError::SerializationError(e.to_string())).ok();
@@ -273,67 +248,384 @@ impl OpenAIInference {
             messages: openai_messages,
             max_tokens: Some(self.max_output_tokens),
             tools,
+            temperature: self.sampling.temperature,
+            top_p: self.sampling.top_p,
+            stop: self.sampling.stop_sequences.clone(),
+            seed: self.sampling.seed,
+            tool_choice: self.sampling.tool_choice.as_ref().map(openai_tool_choice),
         };
 
-        let response = self.client
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", self.api_key))
+        let response = self.post("/chat/completions")?
             .json(&request)
             .send()
             .await
-            .map_err(|e| InferenceError::NetworkError(e.to_string()))?;
+            .map_err(InferenceError::from)?;
 
         let status = response.status();
-        let response_text = response.text().await
-            .map_err(|e| InferenceError::NetworkError(e.to_string()))?;
+        let headers = response.headers().clone();
+        let response_text = http::read_text(response, self.read_timeout).await?;
         log::info!("{:?}", response_text);
 
         if !status.is_success() {
-            return Err(InferenceError::ApiError(status, response_text));
+            return Err(InferenceError::from_response(status, &headers, response_text));
         }
 
-        let openai_response: OpenAIResponse = serde_json::from_str(&response_text)
-            .map_err(|e| InferenceError::InvalidResponse(format!("Failed to parse OpenAI response: {}", e)))?;
+        parse_response(&response_text)
+    }
+}
 
-        if openai_response.choices.is_empty() {
-            return Err(InferenceError::InvalidResponse("No choices in OpenAI response".to_string()));
-        }
+fn role_name(role: &Role) -> &'static str {
+    match role {
+        Role::User => "user",
+        Role::Assistant => "assistant",
+        Role::System => "system",
+        Role::Developer => "developer",
+    }
+}
 
-        let first_choice = &openai_response.choices[0].message;
-        let mut content = first_choice.content.clone();
-
-        // Handle tool calls if present
-        if let Some(tool_calls) = &first_choice.tool_calls {
-            for tool_call in tool_calls {
-                if tool_call.call_type == "function" {
-                    // Parse the arguments as JSON Value
-                    let input: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)
-                        .map_err(|e| InferenceError::SerializationError(format!("Failed to parse tool arguments: {}", e)))?;
-
-                    content.push(ContentItem::ToolUse {
-                        id: tool_call.id.clone(),
-                        name: tool_call.function.name.clone(),
-                        input,
-                    });
+/// `tool_choice` of the chat completions API, which calls "any" required.
+pub(super) fn openai_tool_choice(choice: &ToolChoice) -> serde_json::Value {
+    match choice {
+        ToolChoice::Auto => serde_json::json!("auto"),
+        ToolChoice::Any => serde_json::json!("required"),
+        ToolChoice::None => serde_json::json!("none"),
+        ToolChoice::Tool(name) => serde_json::json!({"type": "function", "function": {"name": name}}),
+    }
+}
+
+/// Translates chat history into OpenAI chat completion messages.  `ToolUse` items become
+/// `tool_calls` on the assistant message and every `ToolResult` becomes its own `tool`
+/// message, so the model sees the calls it made and their results on the next turn.
+pub(super) fn to_openai_messages(messages: Vec<Message>) -> Vec<serde_json::Value> {
+    let mut openai_messages = Vec::new();
+
+    for msg in messages {
+        let text = msg.content.iter()
+            .filter_map(|item| {
+                match item {
+                    ContentItem::Text { text } => Some(text.clone()),
+                    _ => None
                 }
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let tool_calls = msg.content.iter()
+            .filter_map(|item| match item {
+                ContentItem::ToolUse { id, name, input } => Some(serde_json::json!({
+                    "id": id,
+                    "type": "function",
+                    "function": {
+                        "name": name,
+                        "arguments": input.to_string(),
+                    }
+                })),
+                _ => None
+            })
+            .collect::<Vec<_>>();
+
+        // Tool messages have to directly follow the assistant message that made the calls,
+        // so they go before any text the user sent along with the results.
+        for item in &msg.content {
+            if let ContentItem::ToolResult { tool_use_id, content } = item {
+                openai_messages.push(serde_json::json!({
+                    "role": "tool",
+                    "tool_call_id": tool_use_id,
+                    "content": content,
+                }));
             }
         }
 
-        Ok(ModelResponse {
-            content,
-            id: openai_response.id,
-            model: openai_response.model,
-            role: first_choice.role.clone(),
-            message_type: "text".to_string(),
-            stop_reason: openai_response.choices[0].finish_reason.clone(),
-            stop_sequence: None,
-            //usage: Some(Usage {
-            //    input_tokens: openai_response.usage.prompt_tokens,
-            //    cache_creation_input_tokens: 0,
-            //    cache_read_input_tokens: 0,
-            //    output_tokens: openai_response.usage.completion_tokens,
-            //}),
-        })
+        let images = msg.content.iter()
+            .filter_map(|item| match item {
+                ContentItem::Image { source } => source.base64(),
+                _ => None,
+            })
+            .map(|(media_type, data)| serde_json::json!({
+                "type": "image_url",
+                "image_url": { "url": format!("data:{};base64,{}", media_type, data) },
+            }))
+            .collect::<Vec<_>>();
+
+        if !tool_calls.is_empty() {
+            openai_messages.push(serde_json::json!({
+                "role": role_name(&msg.role),
+                "content": if text.is_empty() { serde_json::Value::Null } else { serde_json::Value::String(text) },
+                "tool_calls": tool_calls,
+            }));
+        } else if !images.is_empty() {
+            // Images need the content part form, plain text keeps the simpler string form
+            let mut parts = Vec::new();
+            if !text.is_empty() {
+                parts.push(serde_json::json!({ "type": "text", "text": text }));
+            }
+            parts.extend(images);
+            openai_messages.push(serde_json::json!({
+                "role": role_name(&msg.role),
+                "content": parts,
+            }));
+        } else if !text.is_empty() {
+            openai_messages.push(serde_json::json!({
+                "role": role_name(&msg.role),
+                "content": text,
+            }));
+        }
+    }
+
+    openai_messages
+}
+
+fn parse_response(response_text: &str) -> Result<ModelResponse, InferenceError> {
+    let openai_response: OpenAIResponse = serde_json::from_str(response_text)
+        .map_err(|e| InferenceError::InvalidResponse(format!("Failed to parse OpenAI response: {}", e)))?;
+
+    if openai_response.choices.is_empty() {
+        return Err(InferenceError::InvalidResponse("No choices in OpenAI response".to_string()));
+    }
+
+    let first_choice = &openai_response.choices[0].message;
+    let mut content = first_choice.content.clone();
+
+    // Handle tool calls if present
+    if let Some(tool_calls) = &first_choice.tool_calls {
+        for tool_call in tool_calls {
+            if tool_call.call_type == "function" {
+                // Parse the arguments as JSON Value
+                let input: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)
+                    .map_err(|e| InferenceError::SerializationError(format!("Failed to parse tool arguments: {}", e)))?;
+
+                content.push(ContentItem::ToolUse {
+                    id: tool_call.id.clone(),
+                    name: tool_call.function.name.clone(),
+                    input,
+                });
+            }
+        }
+    }
+
+    Ok(ModelResponse {
+        content,
+        id: openai_response.id,
+        model: openai_response.model,
+        role: first_choice.role.clone(),
+        message_type: "text".to_string(),
+        stop_reason: openai_response.choices[0].finish_reason.clone(),
+        stop_sequence: None,
+        usage: openai_response.usage.map(Usage::from).unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::types::ImageSource;
+
+    const HISTORY: &str = include_str!("../../tests/fixtures/openai/tool_history.json");
+    const EXPECTED_MESSAGES: &str = include_str!("../../tests/fixtures/openai/tool_messages.json");
+    const TOOL_CALL_RESPONSE: &str = include_str!("../../tests/fixtures/openai/tool_call_response.json");
+
+    #[test]
+    fn test_tool_history_serializes_to_openai_messages() -> Result<(), serde_json::Error> {
+        let history: Vec<Message> = serde_json::from_str(HISTORY)?;
+        let expected: serde_json::Value = serde_json::from_str(EXPECTED_MESSAGES)?;
+
+        assert_eq!(serde_json::Value::Array(to_openai_messages(history)), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tool_calls_round_trip() -> Result<(), InferenceError> {
+        let response = parse_response(TOOL_CALL_RESPONSE)?;
+        assert_eq!(response.stop_reason, "tool_calls");
+        assert_eq!(response.usage.input_tokens, 812);
+        assert_eq!(response.usage.output_tokens, 64);
+
+        let assistant = Message { role: Role::Assistant, content: response.content, model: None };
+        let messages = to_openai_messages(vec![assistant]);
+
+        let recorded: serde_json::Value = serde_json::from_str(TOOL_CALL_RESPONSE).unwrap();
+        let recorded_message = &recorded["choices"][0]["message"];
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["content"], recorded_message["content"]);
+        let sent_calls = messages[0]["tool_calls"].as_array().unwrap();
+        let recorded_calls = recorded_message["tool_calls"].as_array().unwrap();
+        assert_eq!(sent_calls.len(), recorded_calls.len());
+        for (sent, recorded) in sent_calls.iter().zip(recorded_calls) {
+            assert_eq!(sent["id"], recorded["id"]);
+            assert_eq!(sent["function"]["name"], recorded["function"]["name"]);
+            // Arguments are a JSON encoded string, whitespace may differ after a round trip
+            let sent_args: serde_json::Value = serde_json::from_str(sent["function"]["arguments"].as_str().unwrap()).unwrap();
+            let recorded_args: serde_json::Value = serde_json::from_str(recorded["function"]["arguments"].as_str().unwrap()).unwrap();
+            assert_eq!(sent_args, recorded_args);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_images_become_content_parts() {
+        let message = Message {
+            role: Role::User,
+            content: vec![
+                ContentItem::Text { text: "What is wrong with this button?".to_string() },
+                ContentItem::Image {
+                    source: ImageSource::Base64 { media_type: "image/png".to_string(), data: "iVBORw0KGgo=".to_string() },
+                },
+            ],
+            model: None,
+        };
+
+        assert_eq!(to_openai_messages(vec![message]), vec![serde_json::json!({
+            "role": "user",
+            "content": [
+                { "type": "text", "text": "What is wrong with this button?" },
+                { "type": "image_url", "image_url": { "url": "data:image/png;base64,iVBORw0KGgo=" } },
+            ],
+        })]);
+    }
+
+    #[tokio::test]
+    async fn test_sampling_is_sent_and_validated() -> Result<(), InferenceError> {
+        use crate::config::{SamplingConfig, ToolChoice};
+        use crate::inference::stub_server::{self, StubResponse};
+
+        let (base_url, requests) = stub_server::spawn(vec![StubResponse::json(TOOL_CALL_RESPONSE)]).await;
+        let sampling = SamplingConfig {
+            temperature: Some(0.3),
+            top_p: Some(0.9),
+            stop_sequences: vec!["```".to_string()],
+            seed: Some(7),
+            tool_choice: Some(ToolChoice::Tool("compile_check".to_string())),
+            ..SamplingConfig::default()
+        };
+        let config = ProjectConfig {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            base_url,
+            api_key: "test-key".to_string(),
+            sampling: sampling.clone(),
+            ..ProjectConfig::default()
+        };
+        let history = vec![Message {
+            role: Role::User,
+            content: vec![ContentItem::Text { text: "Check the build".to_string() }],
+            model: None,
+        }];
+
+        OpenAIInference::from_config(&config).query_model(history.clone(), None).await?;
+        let body = requests.lock().unwrap()[0].json();
+        assert_eq!(body["temperature"], 0.3);
+        assert_eq!(body["top_p"], 0.9);
+        assert_eq!(body["stop"], serde_json::json!(["```"]));
+        assert_eq!(body["seed"], 7);
+        assert_eq!(body["tool_choice"], serde_json::json!({"type": "function", "function": {"name": "compile_check"}}));
+
+        let top_k = ProjectConfig { sampling: SamplingConfig { top_k: Some(40), ..sampling }, ..config };
+        let error = OpenAIInference::from_config(&top_k).query_model(history, None).await.unwrap_err();
+        assert!(matches!(&error, InferenceError::InvalidConfig(msg) if msg.contains("top_k")));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_azure_uses_deployment_url_and_api_key() -> Result<(), InferenceError> {
+        use std::collections::BTreeMap;
+        use crate::config::{AzureConfig, OpenAIConfig};
+        use crate::inference::stub_server::{self, StubResponse};
+
+        let (base_url, requests) = stub_server::spawn(vec![
+            StubResponse::json(TOOL_CALL_RESPONSE),
+            StubResponse::json(TOOL_CALL_RESPONSE),
+        ]).await;
+        let config = ProjectConfig {
+            provider: "azure".to_string(),
+            model: "gpt-4o".to_string(),
+            base_url: format!("{}/", base_url),
+            api_key: "azure-key".to_string(),
+            openai: OpenAIConfig {
+                extra_headers: BTreeMap::from([("x-gateway-team".to_string(), "tools".to_string())]),
+                ..OpenAIConfig::default()
+            },
+            azure: AzureConfig { deployment: Some("gpt4o-prod".to_string()), api_version: None },
+            ..ProjectConfig::default()
+        };
+        let history = vec![Message {
+            role: Role::User,
+            content: vec![ContentItem::Text { text: "Check the build".to_string() }],
+            model: None,
+        }];
+
+        OpenAIInference::azure(&config).query_model(history.clone(), None).await?;
+        let request = requests.lock().unwrap()[0].clone();
+        assert_eq!(request.request_line, format!("POST /openai/deployments/gpt4o-prod/chat/completions?api-version={} HTTP/1.1", AZURE_API_VERSION));
+        assert_eq!(request.header("api-key"), Some("azure-key"));
+        assert_eq!(request.header("authorization"), None);
+        assert_eq!(request.header("x-gateway-team"), Some("tools"));
+
+        // A gateway in front of plain OpenAI that authenticates on its own
+        let gateway = ProjectConfig {
+            provider: "openai".to_string(),
+            base_url,
+            api_key: String::new(),
+            openai: OpenAIConfig {
+                auth_scheme: Some(AuthScheme::None),
+                query_params: BTreeMap::from([("route".to_string(), "eu".to_string())]),
+                ..OpenAIConfig::default()
+            },
+            ..config
+        };
+        OpenAIInference::from_config(&gateway).query_model(history, None).await?;
+        let request = requests.lock().unwrap()[1].clone();
+        assert_eq!(request.request_line, "POST /chat/completions?route=eu HTTP/1.1");
+        assert_eq!(request.header("api-key"), None);
+        assert_eq!(request.header("authorization"), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_extra_headers_replace_auth_and_are_checked() -> Result<(), InferenceError> {
+        use std::collections::BTreeMap;
+        use crate::config::OpenAIConfig;
+        use crate::inference::stub_server::{self, StubResponse};
+
+        let (base_url, requests) = stub_server::spawn(vec![StubResponse::json(TOOL_CALL_RESPONSE)]).await;
+        let config = ProjectConfig {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            base_url,
+            api_key: "openai-key".to_string(),
+            openai: OpenAIConfig {
+                extra_headers: BTreeMap::from([("Authorization".to_string(), "Bearer gateway-token".to_string())]),
+                ..OpenAIConfig::default()
+            },
+            ..ProjectConfig::default()
+        };
+        let history = vec![Message {
+            role: Role::User,
+            content: vec![ContentItem::Text { text: "Check the build".to_string() }],
+            model: None,
+        }];
+
+        OpenAIInference::from_config(&config).query_model(history.clone(), None).await?;
+        let request = requests.lock().unwrap()[0].clone();
+        let auth = request.headers.iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+            .map(|(_, value)| value.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(auth, ["Bearer gateway-token"]);
+
+        // Not retried like a network error, the request is never sent
+        for (name, value) in [("x-team name", "tools"), ("x-team", "tools\n")] {
+            let invalid = ProjectConfig {
+                openai: OpenAIConfig {
+                    extra_headers: BTreeMap::from([(name.to_string(), value.to_string())]),
+                    ..OpenAIConfig::default()
+                },
+                ..config.clone()
+            };
+            let error = OpenAIInference::from_config(&invalid).query_model(history.clone(), None).await.unwrap_err();
+            assert!(matches!(&error, InferenceError::InvalidConfig(msg) if msg.contains("extra_headers")), "{:?}", error);
+        }
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        Ok(())
     }
 }
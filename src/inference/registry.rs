@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::future::Future;
+
+use futures::future::BoxFuture;
+
+use crate::config::ProjectConfig;
+use super::types::Inference;
+use super::{
+    AnthropicInference, AWSBedrockInference, DeepSeekInference, GeminiInference, OllamaInference, OpenAIInference,
+    ReplayInference,
+};
+
+type Constructor = Box<dyn Fn(ProjectConfig) -> BoxFuture<'static, Box<dyn Inference>> + Send + Sync>;
+
+/// Maps the `provider` of a config to the constructor of its inference backend.  The
+/// built-in providers are registered by default, others can be added with `register`.
+pub struct ProviderRegistry {
+    constructors: HashMap<String, Constructor>,
+}
+
+impl Default for ProviderRegistry {
+    fn default() -> Self {
+        let mut registry = ProviderRegistry::empty();
+        registry.register("anthropic", |config| async move { AnthropicInference::from_config(&config) });
+        registry.register("openai", |config| async move { OpenAIInference::from_config(&config) });
+        registry.register("azure", |config| async move { OpenAIInference::azure(&config) });
+        registry.register("deepseek", |config| async move { DeepSeekInference::from_config(&config) });
+        registry.register("ollama", |config| async move { OllamaInference::from_config(&config) });
+        registry.register("gemini", |config| async move { GeminiInference::from_config(&config) });
+        registry.register("bedrock", |config| async move { AWSBedrockInference::from_config(&config).await });
+        registry.register("replay", |config| async move { ReplayInference::from_config(&config).await });
+        registry
+    }
+}
+
+impl ProviderRegistry {
+    /// Providers that aren't registered are assumed to speak the OpenAI API.
+    const FALLBACK_PROVIDER: &'static str = "openai";
+
+    pub fn empty() -> Self {
+        ProviderRegistry { constructors: HashMap::new() }
+    }
+
+    /// Registers a provider, replacing any previously registered provider with the same name.
+    pub fn register<F, Fut, I>(&mut self, name: &str, constructor: F)
+    where
+        F: Fn(ProjectConfig) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = I> + Send + 'static,
+        I: Inference + 'static,
+    {
+        self.constructors.insert(name.to_string(), Box::new(move |config| {
+            let inference = constructor(config);
+            Box::pin(async move { Box::new(inference.await) as Box<dyn Inference> })
+        }));
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.constructors.contains_key(name)
+    }
+
+    /// Builds the inference backend for `config.provider`.
+    pub async fn create(&self, config: &ProjectConfig) -> Option<Box<dyn Inference>> {
+        let constructor = self.constructors.get(&config.provider)
+            .or_else(|| self.constructors.get(Self::FALLBACK_PROVIDER))?;
+        Some(constructor(config.clone()).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::inference::types::{InferenceError, Message, ModelResponse, Usage};
+
+    struct EchoInference {
+        model: String,
+    }
+
+    #[async_trait]
+    impl Inference for EchoInference {
+        async fn query_model(&self, messages: Vec<Message>, _system_message: Option<&str>) -> Result<ModelResponse, InferenceError> {
+            Ok(ModelResponse {
+                content: messages.into_iter().flat_map(|message| message.content).collect(),
+                id: "echo_1".to_string(),
+                model: self.model.clone(),
+                role: "assistant".to_string(),
+                message_type: "text".to_string(),
+                stop_reason: "end_turn".to_string(),
+                stop_sequence: None,
+                usage: Usage::default(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registered_provider_is_created_from_config() -> Result<(), InferenceError> {
+        let mut registry = ProviderRegistry::default();
+        registry.register("echo", |config| async move { EchoInference { model: config.model } });
+        assert!(registry.contains("echo") && registry.contains("anthropic") && registry.contains("azure"));
+
+        let config = ProjectConfig { provider: "echo".to_string(), model: "echo-1".to_string(), ..ProjectConfig::default() };
+        let inference = registry.create(&config).await.unwrap();
+        let response = inference.query_model(Vec::new(), None).await?;
+        assert_eq!(response.model, "echo-1");
+        assert!(inference.count_tokens(Vec::new(), None).await.is_none());
+
+        assert!(ProviderRegistry::empty().create(&config).await.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+
+use crate::config::{ProjectConfig, ReplayMode};
+use super::registry::ProviderRegistry;
+use super::types::{Inference, InferenceError, Message, ModelResponse};
+
+/// Recorded model calls, in the order they were made.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    /// The request after `normalize_request`
+    pub request: serde_json::Value,
+    pub response: ModelResponse,
+}
+
+/// The request as it is recorded and matched.  The model of assistant messages only
+/// matters to the client and the indentation of the system prompt is ignored.
+pub fn normalize_request(messages: &[Message], system_message: Option<&str>) -> serde_json::Value {
+    let messages = messages.iter()
+        .map(|message| Message { model: None, ..message.clone() })
+        .collect::<Vec<_>>();
+    json!({
+        "system": system_message.map(|sys_msg| sys_msg.split_whitespace().collect::<Vec<_>>().join(" ")),
+        "messages": messages,
+    })
+}
+
+/// Answers from a cassette file instead of a model, or records the calls to another
+/// provider into one, so the chat loop can be tested offline.
+pub struct ReplayInference {
+    path: PathBuf,
+    mode: ReplayMode,
+    /// The real provider while recording
+    recorder: Option<Box<dyn Inference>>,
+    cassette: Mutex<Cassette>,
+    /// Interactions that already answered a request, each one is replayed once
+    used: Mutex<Vec<bool>>,
+    load_error: Option<String>,
+}
+
+impl ReplayInference {
+    pub async fn from_config(config: &ProjectConfig) -> Self {
+        let path = PathBuf::from(&config.replay.cassette);
+        let mode = config.replay.mode;
+
+        let (cassette, load_error, recorder) = match mode {
+            ReplayMode::Replay => match Self::load(&path) {
+                Ok(cassette) => (cassette, None, None),
+                Err(e) => (Cassette::default(), Some(e), None),
+            },
+            ReplayMode::Record => {
+                let recorded_config = ProjectConfig { provider: config.replay.provider.clone(), ..config.clone() };
+                let recorder = ProviderRegistry::default().create(&recorded_config).await;
+                (Cassette::default(), None, recorder)
+            },
+        };
+
+        ReplayInference {
+            path,
+            mode,
+            recorder,
+            used: Mutex::new(vec![false; cassette.interactions.len()]),
+            cassette: Mutex::new(cassette),
+            load_error,
+        }
+    }
+
+    fn load(path: &PathBuf) -> Result<Cassette, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read cassette {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse cassette {}: {}", path.display(), e))
+    }
+
+    fn replay(&self, request: &serde_json::Value) -> Result<ModelResponse, InferenceError> {
+        if let Some(error) = &self.load_error {
+            return Err(InferenceError::InvalidResponse(error.clone()));
+        }
+
+        let cassette = self.cassette.lock().unwrap();
+        let mut used = self.used.lock().unwrap();
+        let index = cassette.interactions.iter().enumerate()
+            .position(|(i, interaction)| !used[i] && interaction.request == *request)
+            .ok_or_else(|| {
+                log::warn!("Unrecorded request: {}", request);
+                InferenceError::InvalidResponse(format!("No interaction in {} matches the request", self.path.display()))
+            })?;
+        used[index] = true;
+        Ok(cassette.interactions[index].response.clone())
+    }
+
+    fn record(&self, request: serde_json::Value, response: &ModelResponse) {
+        let mut cassette = self.cassette.lock().unwrap();
+        cassette.interactions.push(Interaction { request, response: response.clone() });
+
+        let saved = self.path.parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&self.path, serde_json::to_string_pretty(&*cassette).unwrap_or_default()));
+        if let Err(e) = saved {
+            log::warn!("Failed to save cassette {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[async_trait]
+impl Inference for ReplayInference {
+    async fn query_model(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, InferenceError> {
+        let request = normalize_request(&messages, system_message);
+        match (self.mode, &self.recorder) {
+            (ReplayMode::Record, Some(recorder)) => {
+                let response = recorder.query_model(messages, system_message).await?;
+                self.record(request, &response);
+                Ok(response)
+            },
+            (ReplayMode::Record, None) => Err(InferenceError::InvalidResponse(
+                "Recording needs the provider to record from in [replay]".to_string()
+            )),
+            (ReplayMode::Replay, _) => self.replay(&request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ReplayConfig;
+    use crate::inference::stub_server::{self, StubResponse};
+    use crate::inference::types::{ContentItem, Role};
+
+    #[tokio::test]
+    async fn test_replays_what_was_recorded() -> Result<(), InferenceError> {
+        let (base_url, requests) = stub_server::spawn(vec![StubResponse::json(
+            include_str!("../../tests/fixtures/openai/tool_call_response.json")
+        )]).await;
+        let dir = tempfile::tempdir().unwrap();
+        let cassette = dir.path().join("cassettes/ping.json");
+        let config = |mode| ProjectConfig {
+            provider: "replay".to_string(),
+            model: "gpt-4o".to_string(),
+            base_url: base_url.clone(),
+            api_key: "test-key".to_string(),
+            replay: ReplayConfig {
+                cassette: cassette.to_string_lossy().to_string(),
+                mode,
+                provider: "openai".to_string(),
+            },
+            ..ProjectConfig::default()
+        };
+        let history = vec![Message {
+            role: Role::User,
+            content: vec![ContentItem::Text { text: "Add a ping module".to_string() }],
+            model: None,
+        }];
+
+        let recorder = ReplayInference::from_config(&config(ReplayMode::Record)).await;
+        let recorded = recorder.query_model(history.clone(), Some("  Be\n    brief.")).await?;
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        let player = ReplayInference::from_config(&config(ReplayMode::Replay)).await;
+        let replayed = player.query_model(history.clone(), Some("Be brief.")).await?;
+        assert_eq!(replayed.content, recorded.content);
+        assert_eq!(replayed.stop_reason, "tool_calls");
+        assert!(player.query_model(history.clone(), Some("Be brief.")).await.is_err());
+        assert!(player.query_model(history, Some("Be verbose.")).await.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
+use serde::Serialize;
+
+use crate::config::RetryConfig;
+use super::types::InferenceError;
+
+/// Reported before waiting out a failed attempt.
+#[derive(Debug, Clone, Serialize)]
+pub struct RetryProgress {
+    /// The attempt that is about to be made, starting at 2
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub delay_ms: u64,
+    pub error: String,
+}
+
+pub fn is_retryable(error: &InferenceError) -> bool {
+    match error {
+        InferenceError::NetworkError(_) | InferenceError::RateLimited(..) | InferenceError::Timeout(_) => true,
+        InferenceError::ApiError(status, _) => {
+            *status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 529 || status.is_server_error()
+        },
+        _ => false,
+    }
+}
+
+/// Exponential backoff with jitter, so concurrent sessions hitting the same limit don't
+/// retry in lockstep.  Picks a delay between half and all of the exponential step.
+fn backoff(config: &RetryConfig, failed_attempts: u32) -> Duration {
+    let exponential = config.initial_backoff_ms
+        .saturating_mul(1u64 << failed_attempts.saturating_sub(1).min(32))
+        .min(config.max_backoff_ms);
+    let random = RandomState::new().hash_one(Instant::now());
+    let jitter = random % (exponential / 2 + 1);
+    Duration::from_millis(exponential - jitter)
+}
+
+/// Runs `operation` until it succeeds, fails with an error that isn't worth retrying, or
+/// runs out of attempts or time.  Server hints (`retry-after` and friends) take precedence
+/// over the computed backoff.
+pub async fn with_retry<T, F, Fut>(
+    config: &RetryConfig,
+    mut on_retry: impl FnMut(&RetryProgress),
+    mut operation: F,
+) -> Result<T, InferenceError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, InferenceError>>,
+{
+    let deadline = Instant::now() + Duration::from_secs(config.deadline_secs);
+    let mut attempt = 1;
+
+    loop {
+        let error = match operation().await {
+            Ok(result) => return Ok(result),
+            Err(error) => error,
+        };
+
+        if attempt >= config.max_attempts || !is_retryable(&error) {
+            return Err(error);
+        }
+
+        let delay = match &error {
+            InferenceError::RateLimited(_, _, Some(retry_after)) => (*retry_after).min(Duration::from_millis(config.max_backoff_ms)),
+            _ => backoff(config, attempt),
+        };
+        if Instant::now() + delay > deadline {
+            return Err(error);
+        }
+
+        attempt += 1;
+        let progress = RetryProgress {
+            attempt,
+            max_attempts: config.max_attempts,
+            delay_ms: delay.as_millis() as u64,
+            error: error.to_string(),
+        };
+        log::info!("Retrying model call: {:?}", progress);
+        on_retry(&progress);
+
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use crate::inference::types::retry_after;
+
+    fn fast_config() -> RetryConfig {
+        RetryConfig {
+            max_attempts: 3,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 5,
+            deadline_secs: 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors_until_success() {
+        let calls = Cell::new(0);
+        let mut reported = Vec::new();
+
+        let result = with_retry(&fast_config(), |p| reported.push(p.attempt), || {
+            calls.set(calls.get() + 1);
+            let call = calls.get();
+            async move {
+                match call {
+                    1 => Err(InferenceError::ApiError(StatusCode::from_u16(529).unwrap(), "overloaded".to_string())),
+                    // Waits no longer than max_backoff_ms, whatever the server asks for
+                    2 => Err(InferenceError::RateLimited(StatusCode::TOO_MANY_REQUESTS, "slow down".to_string(), Some(Duration::from_secs(3600)))),
+                    _ => Ok("done"),
+                }
+            }
+        }).await;
+
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(reported, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_on_client_errors_and_after_max_attempts() {
+        let calls = Cell::new(0);
+        let result: Result<(), _> = with_retry(&fast_config(), |_| {}, || {
+            calls.set(calls.get() + 1);
+            async { Err(InferenceError::ApiError(StatusCode::BAD_REQUEST, "bad".to_string())) }
+        }).await;
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+
+        calls.set(0);
+        let result: Result<(), _> = with_retry(&fast_config(), |_| {}, || {
+            calls.set(calls.get() + 1);
+            async { Err(InferenceError::NetworkError("reset".to_string())) }
+        }).await;
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_retry_after_headers() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("retry-after", "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+        for value in ["inf", "1e30"] {
+            headers.insert("retry-after", value.parse().unwrap());
+            assert_eq!(retry_after(&headers), None);
+        }
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        let reset = humantime::format_rfc3339_seconds(std::time::SystemTime::now() + Duration::from_secs(30)).to_string();
+        headers.insert("anthropic-ratelimit-tokens-remaining", "0".parse().unwrap());
+        headers.insert("anthropic-ratelimit-tokens-reset", reset.parse().unwrap());
+        headers.insert("anthropic-ratelimit-requests-remaining", "10".parse().unwrap());
+        headers.insert("anthropic-ratelimit-requests-reset", reset.parse().unwrap());
+        let delay = retry_after(&headers).unwrap();
+        assert!(delay > Duration::from_secs(25) && delay <= Duration::from_secs(30));
+    }
+}
@@ -0,0 +1,76 @@
+/// A single Server-Sent Event as defined by the `text/event-stream` format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub data: String,
+}
+
+/// Incremental parser for a `text/event-stream` body.  Chunks from the network can split
+/// lines (and UTF-8 characters) anywhere, so bytes are buffered until a full line arrives.
+#[derive(Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let mut line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            let line = String::from_utf8_lossy(&line);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(SseEvent {
+                        event: self.event.take(),
+                        data: self.data.join("\n"),
+                    });
+                    self.data.clear();
+                }
+                self.event = None;
+            } else if line.starts_with(':') {
+                // Comment line, used by servers as keep-alive
+            } else {
+                let (field, value) = match line.split_once(':') {
+                    Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                    None => (line.as_ref(), ""),
+                };
+                match field {
+                    "event" => self.event = Some(value.to_string()),
+                    "data" => self.data.push(value.to_string()),
+                    _ => {}
+                }
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_split_across_chunks() {
+        let mut parser = SseParser::new();
+        assert!(parser.push(b"event: ping\ndata: {\"a\"").is_empty());
+        let events = parser.push(b":1}\r\n\r\n: keep-alive\n\ndata: one\ndata: two\n\n");
+        assert_eq!(events, vec![
+            SseEvent { event: Some("ping".to_string()), data: "{\"a\":1}".to_string() },
+            SseEvent { event: None, data: "one\ntwo".to_string() },
+        ]);
+    }
+}
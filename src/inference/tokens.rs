@@ -0,0 +1,158 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use tiktoken_rs::CoreBPE;
+use tokenizers::Tokenizer;
+
+use crate::config::ProjectConfig;
+use super::types::{ContentItem, Message};
+
+/// Rough cost of an image, Claude bills a full size screenshot at about this many tokens
+const IMAGE_TOKENS: usize = 1600;
+/// Role markers and separators every provider wraps around a message
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Counts tokens the way a model's tokenizer splits text.
+pub trait TokenCounter: Send + Sync {
+    fn count(&self, text: &str) -> usize;
+}
+
+/// OpenAI's BPE encodings, cl100k also stands in for models whose tokenizer isn't public.
+pub struct TiktokenCounter(CoreBPE);
+
+impl TokenCounter for TiktokenCounter {
+    fn count(&self, text: &str) -> usize {
+        self.0.encode_ordinary(text).len()
+    }
+}
+
+/// A Hugging Face `tokenizer.json`, as published for DeepSeek and most open models.
+pub struct HuggingFaceCounter(Tokenizer);
+
+impl HuggingFaceCounter {
+    pub fn from_file(path: &str) -> Result<Self, anyhow::Error> {
+        Tokenizer::from_file(path)
+            .map(HuggingFaceCounter)
+            .map_err(|e| anyhow::anyhow!("Failed to load tokenizer {}: {}", path, e))
+    }
+}
+
+impl TokenCounter for HuggingFaceCounter {
+    fn count(&self, text: &str) -> usize {
+        self.0.encode(text, false).map(|encoding| encoding.len()).unwrap_or_default()
+    }
+}
+
+/// Picks the counter closest to the configured model's own tokenizer.
+pub fn counter_for(config: &ProjectConfig) -> Box<dyn TokenCounter> {
+    if let Some(path) = &config.tokenizer {
+        match HuggingFaceCounter::from_file(path) {
+            Ok(counter) => return Box::new(counter),
+            Err(e) => log::warn!("{}, counting with a BPE encoding instead", e),
+        }
+    }
+
+    let bpe = if uses_o200k(&config.model) {
+        tiktoken_rs::o200k_base()
+    } else {
+        tiktoken_rs::cl100k_base()
+    };
+    Box::new(TiktokenCounter(bpe.expect("Failed to load BPE encoding.")))
+}
+
+fn uses_o200k(model: &str) -> bool {
+    ["gpt-4o", "chatgpt-4o", "gpt-4.1", "gpt-4.5", "o1", "o3", "o4"].iter()
+        .any(|prefix| model.starts_with(prefix))
+}
+
+/// Token counts of the chat history.  Each message is only encoded once, later counts of
+/// the same message come from the cache.
+pub struct ContextCounter {
+    counter: Box<dyn TokenCounter>,
+    cache: HashMap<u64, usize>,
+}
+
+impl ContextCounter {
+    pub fn new(counter: Box<dyn TokenCounter>) -> Self {
+        ContextCounter { counter, cache: HashMap::new() }
+    }
+
+    pub fn count_text(&self, text: &str) -> usize {
+        self.counter.count(text)
+    }
+
+    pub fn count_message(&mut self, message: &Message) -> usize {
+        let key = message_key(message);
+        if let Some(&tokens) = self.cache.get(&key) {
+            return tokens;
+        }
+        let tokens = MESSAGE_OVERHEAD_TOKENS + message.content.iter()
+            .map(|item| self.count_item(item))
+            .sum::<usize>();
+        self.cache.insert(key, tokens);
+        tokens
+    }
+
+    /// Forgets the counts of messages that are no longer in `messages`.
+    pub fn retain(&mut self, messages: &[Message]) {
+        let keys = messages.iter().map(message_key).collect::<HashSet<_>>();
+        self.cache.retain(|key, _| keys.contains(key));
+    }
+
+    fn count_item(&self, item: &ContentItem) -> usize {
+        match item {
+            ContentItem::Text { text } => self.counter.count(text),
+            ContentItem::ToolUse { name, input, .. } => self.counter.count(name) + self.counter.count(&input.to_string()),
+            ContentItem::ToolResult { content, .. } => self.counter.count(content),
+            ContentItem::Image { .. } => IMAGE_TOKENS,
+            ContentItem::Thinking { thinking, .. } => self.counter.count(thinking),
+            // Encrypted reasoning, its size is the best guess there is
+            ContentItem::RedactedThinking { data } => data.len() / 4,
+        }
+    }
+}
+
+fn message_key(message: &Message) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(message).unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::types::Role;
+
+    #[test]
+    fn test_counter_follows_model_tokenizer() {
+        let config = |model: &str| ProjectConfig { model: model.to_string(), ..ProjectConfig::default() };
+        let text = "fn main() { println!(\"héllo wörld\"); }";
+
+        let o200k = counter_for(&config("gpt-4o-mini")).count(text);
+        let cl100k = counter_for(&config("claude-3-5-haiku-latest")).count(text);
+        assert_eq!(o200k, tiktoken_rs::o200k_base().unwrap().encode_ordinary(text).len());
+        assert_eq!(cl100k, tiktoken_rs::cl100k_base().unwrap().encode_ordinary(text).len());
+
+        let missing = ProjectConfig { tokenizer: Some("missing/tokenizer.json".to_string()), ..config("deepseek-chat") };
+        assert_eq!(counter_for(&missing).count(text), cl100k);
+    }
+
+    #[test]
+    fn test_message_counts_are_cached() {
+        let mut counter = ContextCounter::new(counter_for(&ProjectConfig::default()));
+        let message = Message {
+            role: Role::User,
+            content: vec![ContentItem::Text { text: "Add a GET /ping endpoint".to_string() }],
+            model: None,
+        };
+
+        let tokens = counter.count_message(&message);
+        assert_eq!(tokens, MESSAGE_OVERHEAD_TOKENS + counter.count_text("Add a GET /ping endpoint"));
+        assert_eq!(counter.cache.len(), 1);
+        assert_eq!(counter.count_message(&message), tokens);
+
+        counter.retain(&[]);
+        assert!(counter.cache.is_empty());
+    }
+}
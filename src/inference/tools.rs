@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
+use crate::tools::{Tool, ToolRegistry};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AnthropicTool {
     pub name: String,
@@ -38,4 +40,106 @@ pub struct PropertySchema {
     #[serde(rename = "type")]
     pub property_type: String,
     pub description: String,
-}
\ No newline at end of file
+    /// Schema of the elements of an array property
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<InputSchema>>,
+}
+impl InputSchema {
+    /// Builds an object schema from `(name, type, description)` triples.
+    pub fn object(properties: &[(&str, &str, &str)], required: &[&str]) -> Self {
+        InputSchema {
+            schema_type: "object".to_string(),
+            properties: properties.iter()
+                .map(|(name, property_type, description)| {
+                    (
+                        name.to_string(),
+                        PropertySchema {
+                            property_type: property_type.to_string(),
+                            description: description.to_string(),
+                            items: None,
+                        },
+                    )
+                })
+                .collect(),
+            required: required.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    /// Makes `property` an array holding `items`.
+    pub fn with_items(mut self, property: &str, items: InputSchema) -> Self {
+        if let Some(schema) = self.properties.get_mut(property) {
+            schema.property_type = "array".to_string();
+            schema.items = Some(Box::new(items));
+        }
+        self
+    }
+}
+
+impl From<&dyn Tool> for AnthropicTool {
+    fn from(tool: &dyn Tool) -> Self {
+        AnthropicTool {
+            name: tool.name().to_string(),
+            description: tool.description().to_string(),
+            input_schema: tool.input_schema(),
+        }
+    }
+}
+
+impl From<&dyn Tool> for OpenAITool {
+    fn from(tool: &dyn Tool) -> Self {
+        OpenAITool {
+            name: tool.name().to_string(),
+            description: tool.description().to_string(),
+            tool_type: "function".to_string(),
+            function: OpenAIToolFunction {
+                description: tool.description().to_string(),
+                name: tool.name().to_string(),
+                parameters: tool.input_schema(),
+            },
+        }
+    }
+}
+
+impl ToolRegistry {
+    pub fn anthropic_tools(&self) -> Vec<AnthropicTool> {
+        self.iter().map(AnthropicTool::from).collect()
+    }
+
+    pub fn openai_tools(&self) -> Vec<OpenAITool> {
+        self.iter().map(OpenAITool::from).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_serializes_to_every_wire_format() -> Result<(), serde_json::Error> {
+        let registry = ToolRegistry::default();
+
+        let anthropic = serde_json::to_value(registry.anthropic_tools())?;
+        let read_file = anthropic.as_array().unwrap().iter()
+            .find(|t| t["name"] == "read_file")
+            .unwrap();
+        assert_eq!(read_file["input_schema"]["type"], "object");
+        assert_eq!(read_file["input_schema"]["required"], serde_json::json!(["path"]));
+
+        let openai = serde_json::to_value(registry.openai_tools())?;
+        let write_file = openai.as_array().unwrap().iter()
+            .find(|t| t["function"]["name"] == "write_file")
+            .unwrap();
+        assert_eq!(write_file["type"], "function");
+        assert_eq!(write_file["function"]["parameters"]["properties"]["content"]["type"], "string");
+        let edit_file = anthropic.as_array().unwrap().iter()
+            .find(|t| t["name"] == "edit_file")
+            .unwrap();
+        assert_eq!(edit_file["input_schema"]["properties"]["edits"]["type"], "array");
+        assert_eq!(edit_file["input_schema"]["properties"]["edits"]["items"]["required"], serde_json::json!(["old_string", "new_string"]));
+        assert!(read_file["input_schema"]["properties"]["path"].get("items").is_none());
+
+        assert_eq!(anthropic.as_array().unwrap().len(), registry.iter().count());
+        assert_eq!(openai.as_array().unwrap().len(), registry.iter().count());
+        Ok(())
+    }
+}
@@ -1,5 +1,10 @@
+use std::pin::Pin;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use futures::{future, stream, Stream};
 use serde::{Serialize, Deserialize};
-use serde::de::Error as SerdeError;
 use anyhow::Result;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -20,12 +25,81 @@ pub enum ContentItem {
         tool_use_id: String,
         content: String,
     },
+    #[serde(rename = "image")]
+    Image {
+        source: ImageSource,
+    },
+    /// Model reasoning.  Anthropic signs it and requires it back unchanged on the turn
+    /// that follows a tool use, other providers leave the signature empty.
+    #[serde(rename = "thinking")]
+    Thinking {
+        thinking: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        signature: String,
+    },
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking {
+        data: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageSource {
+    Base64 {
+        media_type: String,
+        data: String,
+    },
+    /// Image file relative to the project root, read into `Base64` before it reaches a model
+    Path {
+        path: String,
+    },
+}
+
+impl ImageSource {
+    pub const MEDIA_TYPES: [&'static str; 4] = ["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+    pub fn from_bytes(media_type: &str, bytes: &[u8]) -> Result<Self, InferenceError> {
+        if !Self::MEDIA_TYPES.contains(&media_type) {
+            return Err(InferenceError::UnsupportedContent(format!(
+                "Unsupported image type {}, expected one of {}", media_type, Self::MEDIA_TYPES.join(", ")
+            )));
+        }
+        Ok(ImageSource::Base64 {
+            media_type: media_type.to_string(),
+            data: BASE64_STANDARD.encode(bytes),
+        })
+    }
+
+    /// Media type and base64 data, `None` for a path that hasn't been read yet.
+    pub fn base64(&self) -> Option<(&str, &str)> {
+        match self {
+            ImageSource::Base64 { media_type, data } => Some((media_type, data)),
+            ImageSource::Path { .. } => None,
+        }
+    }
+}
+
+impl ContentItem {
+    pub fn is_thinking(&self) -> bool {
+        matches!(self, ContentItem::Thinking { .. } | ContentItem::RedactedThinking { .. })
+    }
+
+    /// Reasoning without a signature came from a provider that doesn't sign it, Anthropic
+    /// rejects it so it can only be shown, never sent back.
+    pub fn is_unsigned_thinking(&self) -> bool {
+        matches!(self, ContentItem::Thinking { signature, .. } if signature.is_empty())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct Message {
     pub role: Role,
     pub content: Vec<ContentItem>,
+    /// Model that produced an assistant message, for the client only and never sent to a
+    /// provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -47,68 +121,72 @@ pub struct ModelResponse {
     pub message_type: String,
     pub stop_reason: String,
     pub stop_sequence: Option<String>,
+    #[serde(default)]
+    pub usage: Usage,
 }
 
-impl ModelResponse {
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, serde_json::Error> {
-        let value: serde_json::Value = serde_json::from_slice(bytes)?;
-        
-        let content = value.get("content")
-            .and_then(|v| v.as_array())
-            .ok_or_else(|| serde_json::Error::missing_field("content"))?
-            .iter()
-            .map(ContentItem::deserialize)
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let id = value.get("id")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| serde_json::Error::missing_field("id"))?
-            .to_string();
-
-        let model = value.get("model")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| serde_json::Error::missing_field("model"))?
-            .to_string();
-
-        let role = value.get("role")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| serde_json::Error::missing_field("role"))?
-            .to_string();
-
-        let message_type = value.get("type")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| serde_json::Error::missing_field("type"))?
-            .to_string();
-
-        let stop_reason = value.get("stop_reason")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| serde_json::Error::missing_field("stop_reason"))?
-            .to_string();
-
-        // Optional fields
-        let stop_sequence = value.get("stop_sequence")
-            .and_then(|v| v.as_str())
-            .map(String::from);
-
-        Ok(ModelResponse {
-            content,
-            id,
-            model,
-            role,
-            message_type,
-            stop_reason,
-            stop_sequence,
-        })
+/// Token counts for a single model call.  `input_tokens` only counts input that was
+/// neither written to nor read from a prompt cache, matching how Anthropic reports it,
+/// so every field is billed at its own rate.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    #[serde(default, deserialize_with = "null_as_zero")]
+    pub input_tokens: u64,
+    #[serde(default, deserialize_with = "null_as_zero")]
+    pub output_tokens: u64,
+    #[serde(default, deserialize_with = "null_as_zero")]
+    pub cache_creation_input_tokens: u64,
+    #[serde(default, deserialize_with = "null_as_zero")]
+    pub cache_read_input_tokens: u64,
+}
+
+impl std::ops::AddAssign for Usage {
+    fn add_assign(&mut self, other: Self) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
     }
 }
 
+fn null_as_zero<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<u64>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Incremental output of a streaming model call.  A well formed stream always ends with
+/// `Done`, which carries the fully assembled response for the chat history.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    TextDelta { text: String },
+    ThinkingDelta { thinking: String },
+    ToolUseStart { id: String, name: String },
+    ToolUseInputDelta { id: String, partial_json: String },
+    ToolUse { tool_use: ContentItem },
+    Done { response: ModelResponse },
+}
+
+pub type EventStream = Pin<Box<dyn Stream<Item = Result<StreamEvent, InferenceError>> + Send>>;
+
 #[derive(Debug)]
 pub enum InferenceError {
     NetworkError(String),
     ApiError(reqwest::StatusCode, String),
+    RateLimited(reqwest::StatusCode, String, Option<Duration>),
     InvalidResponse(String),
     MissingApiKey(String),
     SerializationError(String),
+    /// The request holds content the model can't take, like an image for a text-only model
+    UnsupportedContent(String),
+    /// `pprog.toml` asks for something the provider can't do
+    InvalidConfig(String),
+    /// The provider didn't connect, answer or finish within the configured timeouts
+    Timeout(String),
+    /// The call was aborted through `/cancel`
+    Cancelled,
 }
 
 impl std::fmt::Display for InferenceError {
@@ -116,15 +194,81 @@ impl std::fmt::Display for InferenceError {
         match self {
             InferenceError::NetworkError(msg) => write!(f, "Network error: {}", msg),
             InferenceError::ApiError(status, msg) => write!(f, "API error ({}): {}", status, msg),
+            InferenceError::RateLimited(status, msg, _) => write!(f, "Rate limited ({}): {}", status, msg),
             InferenceError::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
             InferenceError::MissingApiKey(msg) => write!(f, "Missing API key: {}", msg),
             InferenceError::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
+            InferenceError::UnsupportedContent(msg) => write!(f, "Unsupported content: {}", msg),
+            InferenceError::InvalidConfig(msg) => write!(f, "Invalid config: {}", msg),
+            InferenceError::Timeout(msg) => write!(f, "Timed out: {}", msg),
+            InferenceError::Cancelled => write!(f, "Cancelled"),
         }
     }
 }
 
 impl std::error::Error for InferenceError {}
 
-pub trait Inference {
+impl From<reqwest::Error> for InferenceError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            InferenceError::Timeout(error.to_string())
+        } else {
+            InferenceError::NetworkError(error.to_string())
+        }
+    }
+}
+
+impl InferenceError {
+    /// Builds the error for a failed HTTP response.  Rate limit and overload responses
+    /// (429 and Anthropic's 529) keep the server's hint on when to try again.
+    pub fn from_response(status: reqwest::StatusCode, headers: &reqwest::header::HeaderMap, body: String) -> Self {
+        match status.as_u16() {
+            429 | 529 => InferenceError::RateLimited(status, body, retry_after(headers)),
+            _ => InferenceError::ApiError(status, body),
+        }
+    }
+}
+
+/// Reads how long to wait before retrying from `retry-after` (seconds or an HTTP date) or,
+/// failing that, from the reset time of any exhausted `anthropic-ratelimit-*` limit.
+pub fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let until = |time: SystemTime| time.duration_since(SystemTime::now()).unwrap_or_default();
+
+    if let Some(value) = header("retry-after") {
+        if let Ok(seconds) = value.trim().parse::<f64>() {
+            // Values too large for a Duration, like `inf`, are ignored rather than trusted
+            return Duration::try_from_secs_f64(seconds.max(0.0)).ok();
+        }
+        if let Ok(time) = httpdate::parse_http_date(value) {
+            return Some(until(time));
+        }
+    }
+
+    ["requests", "tokens", "input-tokens", "output-tokens"].iter()
+        .filter(|limit| header(&format!("anthropic-ratelimit-{}-remaining", limit)) == Some("0"))
+        .filter_map(|limit| header(&format!("anthropic-ratelimit-{}-reset", limit)))
+        .filter_map(|reset| humantime::parse_rfc3339_weak(reset).ok())
+        .map(until)
+        .max()
+}
+
+/// A model provider.  `query_model` is all a provider needs, those that can stream or
+/// count tokens override the other methods.
+#[async_trait]
+pub trait Inference: Send + Sync {
     async fn query_model(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<ModelResponse, InferenceError>;
+
+    /// Streams the response, by default the whole response is delivered as a single
+    /// `StreamEvent::Done`.
+    async fn query_model_stream(&self, messages: Vec<Message>, system_message: Option<&str>) -> Result<EventStream, InferenceError> {
+        let response = self.query_model(messages, system_message).await?;
+        Ok(Box::pin(stream::once(future::ready(Ok(StreamEvent::Done { response })))))
+    }
+
+    /// Exact number of input tokens the request would use, `None` when the provider can't
+    /// count them.
+    async fn count_tokens(&self, _messages: Vec<Message>, _system_message: Option<&str>) -> Option<Result<u64, InferenceError>> {
+        None
+    }
 }
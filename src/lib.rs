@@ -0,0 +1,7 @@
+pub mod inference;
+pub mod chat;
+pub mod tree;
+pub mod config;
+pub mod server;
+pub mod tools;
+pub mod usage;
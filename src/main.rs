@@ -1,16 +1,9 @@
-mod inference;
-mod chat;
-mod tree;
-mod config;
-mod server;
-
 use std::fs::OpenOptions;
 use std::io::Write;
 
 use clap::{CommandFactory, Parser, Subcommand};
-use config::ProjectConfig;
 use env_logger::{Builder, Target};
-use tree::GitTree;
+use pprog::{config::ProjectConfig, server, tree::GitTree};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -84,7 +77,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 if !gitignore_contents.contains("pprog.toml") {
                     println!("Adding config to .gitignore.");
                     let mut gitignore = std::fs::OpenOptions::new()
-                        .write(true)
                         .append(true)
                         .create(true)
                         .open(gitignore_path)
@@ -4,14 +4,21 @@ use handlebars::Handlebars;
 use include_dir::{include_dir, Dir};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::sync::Mutex;
+use tokio::sync::Mutex;
 use std::collections::HashMap;
 use actix_web::http;
 use std::process::Command;
 use std::str;
 
-use crate::chat::Chat;
-use crate::inference::types::{Message, Role, ContentItem, InferenceError};
+use futures::stream;
+use tokio::sync::mpsc;
+
+use crate::chat::{CancelHandle, Chat, ChatEvent};
+use crate::tools::Approvals;
+use crate::inference::types::{Message, Role, ContentItem, ImageSource, InferenceError};
+
+/// Limit for request bodies, which carry images as base64 in chat messages
+const MAX_REQUEST_BYTES: usize = 20 * 1024 * 1024;
 
 #[derive(Deserialize)]
 pub struct ChatRequest {
@@ -37,9 +44,25 @@ pub struct DiffResponse {
 
 pub struct AppState {
     chat: Mutex<Chat>,
+    /// Kept outside the lock, which the call being cancelled is holding
+    cancel: CancelHandle,
+    /// Also outside the lock, a command waiting for approval holds it
+    approvals: Approvals,
     static_files: HashMap<String, Vec<u8>>,
 }
 
+impl AppState {
+    /// State for serving `chat` without the frontend files.
+    pub fn new(chat: Chat) -> Self {
+        AppState {
+            cancel: chat.cancel_handle(),
+            approvals: chat.approvals(),
+            chat: Mutex::new(chat),
+            static_files: HashMap::new(),
+        }
+    }
+}
+
 static DIST_DIR: Dir = include_dir!("./frontend/dist/");
 
 // Rest of the existing code remains the same
@@ -63,55 +86,121 @@ fn parse_error_message(message: &str) -> Value {
     }
 }
 
-fn handle_inference_error(error: InferenceError) -> HttpResponse {
+fn inference_error_response(error: InferenceError) -> ErrorResponse {
     match error {
-        InferenceError::NetworkError(msg) => {
-            HttpResponse::ServiceUnavailable().json(ErrorResponse {
-                error: parse_error_message(&msg),
-                error_type: "network_error".to_string(),
-                status_code: 503,
-            })
-        }
-        InferenceError::ApiError(status, msg) => {
-            HttpResponse::build(status).json(ErrorResponse {
-                error: parse_error_message(&msg),
-                error_type: "api_error".to_string(),
-                status_code: status.as_u16(),
-            })
-        }
-        InferenceError::InvalidResponse(msg) => {
-            HttpResponse::BadGateway().json(ErrorResponse {
-                error: parse_error_message(&msg),
-                error_type: "invalid_response".to_string(),
-                status_code: 502,
-            })
+        InferenceError::NetworkError(msg) => ErrorResponse {
+            error: parse_error_message(&msg),
+            error_type: "network_error".to_string(),
+            status_code: 503,
+        },
+        InferenceError::ApiError(status, msg) => ErrorResponse {
+            error: parse_error_message(&msg),
+            error_type: "api_error".to_string(),
+            status_code: status.as_u16(),
+        },
+        InferenceError::RateLimited(status, msg, _) => ErrorResponse {
+            error: parse_error_message(&msg),
+            error_type: "rate_limit_error".to_string(),
+            status_code: status.as_u16(),
+        },
+        InferenceError::InvalidResponse(msg) => ErrorResponse {
+            error: parse_error_message(&msg),
+            error_type: "invalid_response".to_string(),
+            status_code: 502,
+        },
+        InferenceError::MissingApiKey(msg) => ErrorResponse {
+            error: parse_error_message(&msg),
+            error_type: "configuration_error".to_string(),
+            status_code: 500,
+        },
+        InferenceError::SerializationError(msg) => ErrorResponse {
+            error: parse_error_message(&msg),
+            error_type: "serialization_error".to_string(),
+            status_code: 500,
+        },
+        InferenceError::UnsupportedContent(msg) => invalid_request_response(&msg),
+        InferenceError::InvalidConfig(msg) => ErrorResponse {
+            error: parse_error_message(&msg),
+            error_type: "configuration_error".to_string(),
+            status_code: 500,
+        },
+        InferenceError::Timeout(msg) => ErrorResponse {
+            error: parse_error_message(&msg),
+            error_type: "timeout_error".to_string(),
+            status_code: 504,
+        },
+        // The client closed the request, as nginx reports it
+        InferenceError::Cancelled => ErrorResponse {
+            error: Value::String("Cancelled by the user".to_string()),
+            error_type: "cancelled".to_string(),
+            status_code: 499,
+        },
+    }
+}
+
+fn chat_error_response(error: anyhow::Error) -> ErrorResponse {
+    match error.downcast::<InferenceError>() {
+        Ok(inference_error) => inference_error_response(inference_error),
+        Err(other_error) => ErrorResponse {
+            error: parse_error_message(&other_error.to_string()),
+            error_type: "unknown_error".to_string(),
+            status_code: 500,
         }
-        InferenceError::MissingApiKey(msg) => {
-            HttpResponse::InternalServerError().json(ErrorResponse {
-                error: parse_error_message(&msg),
-                error_type: "configuration_error".to_string(),
-                status_code: 500,
-            })
+    }
+}
+
+fn invalid_request_response(message: &str) -> ErrorResponse {
+    ErrorResponse {
+        error: Value::String(message.to_string()),
+        error_type: "invalid_request_error".to_string(),
+        status_code: 400,
+    }
+}
+
+fn tool_error_response(error: anyhow::Error) -> ErrorResponse {
+    match error.downcast::<InferenceError>() {
+        Ok(inference_error) => inference_error_response(inference_error),
+        Err(other_error) => ErrorResponse {
+            error: parse_error_message(&other_error.to_string()),
+            error_type: "tool_error".to_string(),
+            status_code: 500,
         }
-        InferenceError::SerializationError(msg) => {
-            HttpResponse::InternalServerError().json(ErrorResponse {
-                error: parse_error_message(&msg),
-                error_type: "serialization_error".to_string(),
-                status_code: 500,
-            })
+    }
+}
+
+impl ErrorResponse {
+    fn into_http_response(self) -> HttpResponse {
+        let status = http::StatusCode::from_u16(self.status_code)
+            .unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+        HttpResponse::build(status).json(self)
+    }
+}
+
+impl From<ErrorResponse> for ChatEvent {
+    fn from(error: ErrorResponse) -> Self {
+        ChatEvent::Error {
+            error: error.error,
+            error_type: error.error_type,
+            status_code: error.status_code,
         }
     }
 }
 
 #[get("/messages")]
 async fn get_messages(data: web::Data<AppState>) -> impl Responder {
-    let chat = data.chat.lock().unwrap();
+    let chat = data.chat.lock().await;
     HttpResponse::Ok().json(&chat.messages)
 }
 
+#[get("/usage")]
+async fn get_usage(data: web::Data<AppState>) -> impl Responder {
+    let chat = data.chat.lock().await;
+    HttpResponse::Ok().json(&chat.usage)
+}
+
 #[get("/clear")]
 async fn clear_chat(data: web::Data<AppState>) -> impl Responder {
-    let mut chat = data.chat.lock().unwrap();
+    let mut chat = data.chat.lock().await;
     let system_prompt = chat.messages.first().filter(|msg| msg.role == Role::System).cloned();
     chat.messages.clear();
     if let Some(prompt) = system_prompt {
@@ -120,6 +209,32 @@ async fn clear_chat(data: web::Data<AppState>) -> impl Responder {
     HttpResponse::Ok().json(json!({"cleared": true, "message": "Chat history cleared"}))
 }
 
+/// Aborts the model call or tool the chat is busy with.  The request waiting on it fails
+/// with a `cancelled` error and the history is left as it was before.
+async fn cancel_handler(data: web::Data<AppState>) -> impl Responder {
+    let cancelled = data.cancel.cancel();
+    HttpResponse::Ok().json(json!({"cancelled": cancelled}))
+}
+
+/// Commands of the running tool calls that wait for the user, see `[commands]` in the config.
+#[get("/approvals")]
+async fn get_approvals(data: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(data.approvals.pending())
+}
+
+#[derive(Deserialize)]
+pub struct ApproveRequest {
+    id: u64,
+    approved: bool,
+}
+
+/// Lets a command waiting in `/approvals` run, or makes its tool call fail with a
+/// `command_blocked` result for the model.
+async fn approve_handler(data: web::Data<AppState>, req: web::Json<ApproveRequest>) -> impl Responder {
+    let resolved = data.approvals.resolve(req.id, req.approved);
+    HttpResponse::Ok().json(json!({"resolved": resolved}))
+}
+
 #[get("/diff")]
 async fn get_diff() -> impl Responder {
     // Run git diff command
@@ -151,27 +266,30 @@ async fn get_diff() -> impl Responder {
     * The handler works by bouncing messages back and forth from client in a sequential manner.
     *
     * When new text input from client arrives, message is sent to third party API and response
-    * then forwared to client.  If the client receives a tool_use (or tool_call for DeepSeek) then
-    * it will immediately send back a tool_use message and this handler will NOT query third party
-    * API and instead handle the tool use.  The tool_result is then sent back to client, processed
-    * by client and immediately sent back to third party API.  That response is then forwarded to
-    * client and this continues until there are no more tool_use messages.
+    * then forwared to client.  If the client receives tool_use items (or tool_call for DeepSeek)
+    * then it will immediately send them back in a message and this handler will NOT query third
+    * party API and instead run all of them.  The tool_results are then sent back to client in a
+    * single message, processed by client and immediately sent back to third party API.  That
+    * response is then forwarded to client and this continues until there are no more tool_use
+    * messages.
     *
-    * The messages coming from client are of type Message to make parsing easier but are guaranteed 
-    * to only have a single content item when sent by client.
+    * The messages coming from client are of type Message to make parsing easier.  The kind of
+    * message is decided by its first content item: text (possibly with images returned by
+    * /upload), tool uses to run, or tool results to send to the model.
     *
 */
 async fn chat_handler(
     data: web::Data<AppState>, 
     req: web::Json<ChatRequest>
 ) -> impl Responder {
-    let mut chat = data.chat.lock().unwrap();
+    let mut chat = data.chat.lock().await;
 
     match &req.0.message.content[0] {
-        ContentItem::Text { .. } => {
+        ContentItem::Text { .. } | ContentItem::Image { .. } => {
             let new_msg = Message {
                 role: Role::User,
-                content: vec![req.0.message.content[0].clone()]
+                content: user_content(&req.0.message.content),
+                model: None,
             };
 
             match chat.send_message(new_msg).await {
@@ -180,44 +298,20 @@ async fn chat_handler(
                         message: returned_msg,
                     })
                 },
-                Err(e) => {
-                    match e.downcast::<InferenceError>() {
-                        Ok(inference_error) => handle_inference_error(inference_error),
-                        Err(other_error) => HttpResponse::InternalServerError().json(ErrorResponse {
-                            error: parse_error_message(&other_error.to_string()),
-                            error_type: "unknown_error".to_string(),
-                            status_code: 500,
-                        })
-                    }
-                }
+                Err(e) => chat_error_response(e).into_http_response(),
             }
         },
-        ContentItem::ToolUse { id, .. } => {
-            match chat.handle_tool_use(&req.0.message.content[0]).await {
-                Ok(tool_use_result) => HttpResponse::Ok().json(ChatResponse {
-                    message: Message {
-                        role: Role::User,
-                        content: vec![
-                            ContentItem::ToolResult {
-                                tool_use_id: id.to_string(),
-                                content: tool_use_result
-                            }
-                        ]
-                    }
-                }),
-                Err(e) => {
-                    HttpResponse::InternalServerError().json(ErrorResponse {
-                        error: parse_error_message(&e.to_string()),
-                        error_type: "tool_error".to_string(),
-                        status_code: 500,
-                    })
-                }
+        ContentItem::ToolUse { .. } => {
+            match chat.handle_tool_uses(&tool_uses(&req.0.message.content)).await {
+                Ok(message) => HttpResponse::Ok().json(ChatResponse { message }),
+                Err(e) => tool_error_response(e).into_http_response(),
             }
         },
         ContentItem::ToolResult { .. } => {
             let msg = Message {
                 role: Role::User,
                 content: req.0.message.content.clone(),
+                model: None,
             };
             
             match chat.send_message(msg).await {
@@ -226,19 +320,106 @@ async fn chat_handler(
                         message: returned_msg,
                     })
                 },
-                Err(e) => {
-                    match e.downcast::<InferenceError>() {
-                        Ok(inference_error) => handle_inference_error(inference_error),
-                        Err(other_error) => HttpResponse::InternalServerError().json(ErrorResponse {
-                            error: parse_error_message(&other_error.to_string()),
-                            error_type: "unknown_error".to_string(),
-                            status_code: 500,
-                        })
-                    }
+                Err(e) => chat_error_response(e).into_http_response(),
+            }
+        },
+        ContentItem::Thinking { .. } | ContentItem::RedactedThinking { .. } => {
+            invalid_request_response("Thinking content is produced by the model and can not be sent").into_http_response()
+        },
+    }
+}
+
+/// Text and images the user typed or uploaded, anything else in a user message is dropped.
+fn user_content(content: &[ContentItem]) -> Vec<ContentItem> {
+    content.iter()
+        .filter(|item| matches!(item, ContentItem::Text { .. } | ContentItem::Image { .. }))
+        .cloned()
+        .collect()
+}
+
+/// Takes the raw bytes of an image with its `Content-Type` and returns the image content
+/// item, which the client sends along with its next text message.
+async fn upload_handler(req: HttpRequest, body: web::Bytes) -> impl Responder {
+    let media_type = req.headers()
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    match ImageSource::from_bytes(media_type, &body) {
+        Ok(source) => HttpResponse::Ok().json(json!({ "content": ContentItem::Image { source } })),
+        Err(e) => inference_error_response(e).into_http_response(),
+    }
+}
+
+/// Every tool use in a message from the client, which runs them all in one go.
+fn tool_uses(content: &[ContentItem]) -> Vec<ContentItem> {
+    content.iter()
+        .filter(|item| matches!(item, ContentItem::ToolUse { .. }))
+        .cloned()
+        .collect()
+}
+
+fn sse_frame(event: &ChatEvent) -> web::Bytes {
+    let data = serde_json::to_value(event).unwrap_or(Value::Null);
+    let event_type = data.get("type").and_then(|t| t.as_str()).unwrap_or("message");
+    web::Bytes::from(format!("event: {}\ndata: {}\n\n", event_type, data))
+}
+
+/*
+    * Same protocol as `chat_handler`, but the response is a `text/event-stream` of `ChatEvent`s
+    * instead of a single JSON body.  Model turns stream `text_delta`, `tool_use_start`,
+    * `tool_use_input_delta` and `tool_use` events while the model generates and end with a
+    * `message` event holding the full assistant message.  Providers that can't stream only
+    * send the final `message`.  Tool use requests from the client are answered with a single
+    * `tool_result` event holding the results of all of them.  Failures are sent as an `error` event with the same body as the
+    * JSON error responses.
+*/
+async fn chat_stream_handler(
+    data: web::Data<AppState>,
+    req: web::Json<ChatRequest>
+) -> impl Responder {
+    let (tx, rx) = mpsc::unbounded_channel::<ChatEvent>();
+    let request = req.into_inner();
+
+    actix_web::rt::spawn(async move {
+        let mut chat = data.chat.lock().await;
+
+        match &request.message.content[0] {
+            ContentItem::ToolUse { .. } => {
+                match chat.handle_tool_uses(&tool_uses(&request.message.content)).await {
+                    Ok(message) => { let _ = tx.send(ChatEvent::ToolResult { message }); },
+                    Err(e) => { let _ = tx.send(tool_error_response(e).into()); },
+                }
+            },
+            first if first.is_thinking() => {
+                let _ = tx.send(invalid_request_response("Thinking content is produced by the model and can not be sent").into());
+            },
+            first => {
+                let content = match first {
+                    ContentItem::Text { .. } | ContentItem::Image { .. } => user_content(&request.message.content),
+                    _ => request.message.content.clone(),
+                };
+                let msg = Message {
+                    role: Role::User,
+                    content,
+                    model: None,
+                };
+                if let Err(e) = chat.send_message_streaming(msg, &tx).await {
+                    let _ = tx.send(chat_error_response(e).into());
                 }
             }
         }
-    }
+    });
+
+    let body = stream::unfold(rx, |mut rx| async move {
+        rx.recv().await
+            .map(|event| (Ok::<_, actix_web::Error>(sse_frame(&event)), rx))
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((http::header::CACHE_CONTROL, "no-cache"))
+        .streaming(body)
 }
 
 fn process_files(dir: &Dir, base_path: &str, static_files: &mut HashMap<String, Vec<u8>>, hbs: &mut Handlebars, template_data: &serde_json::Value) {
@@ -269,6 +450,11 @@ fn process_files(dir: &Dir, base_path: &str, static_files: &mut HashMap<String,
 }
 
 pub async fn start_server(host: String, port: u16) -> std::io::Result<()> {
+    serve(Chat::new().await, host, port).await
+}
+
+/// Serves the web interface for `chat`, which can be set up with providers of its own.
+pub async fn serve(chat: Chat, host: String, port: u16) -> std::io::Result<()> {
     let server_url = format!("http://{}:{}", host, port);
     let template_data = json!({
         "server_url": server_url
@@ -279,10 +465,7 @@ pub async fn start_server(host: String, port: u16) -> std::io::Result<()> {
     
     process_files(&DIST_DIR, "", &mut static_files, &mut hbs, &template_data);
 
-    let app_state = web::Data::new(AppState {
-        chat: Mutex::new(Chat::new().await),
-        static_files,
-    });
+    let app_state = web::Data::new(AppState { static_files, ..AppState::new(chat) });
 
     println!("Starting server on {}:{}", host, port);
 
@@ -303,17 +486,30 @@ pub async fn start_server(host: String, port: u16) -> std::io::Result<()> {
         App::new()
             .wrap(cors)
             .app_data(app_state.clone())
-            .route("/chat", web::post().to(chat_handler))
-            .service(clear_chat)
-            .service(get_messages)
-            .service(get_diff)
-            .service(index)
+            .configure(routes)
     })
     .bind(format!("{}:{}", host, port))?
     .run()
     .await
 }
 
+/// Registers the API and the frontend, the app needs `AppState` as app data.
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(web::JsonConfig::default().limit(MAX_REQUEST_BYTES))
+        .app_data(web::PayloadConfig::new(MAX_REQUEST_BYTES))
+        .route("/chat", web::post().to(chat_handler))
+        .route("/upload", web::post().to(upload_handler))
+        .route("/chat/stream", web::post().to(chat_stream_handler))
+        .route("/cancel", web::post().to(cancel_handler))
+        .route("/approve", web::post().to(approve_handler))
+        .service(clear_chat)
+        .service(get_messages)
+        .service(get_usage)
+        .service(get_approvals)
+        .service(get_diff)
+        .service(index);
+}
+
 #[get("/{filename:.*}")]
 async fn index(
     req: HttpRequest, 
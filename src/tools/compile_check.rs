@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+
+use crate::inference::tools::InputSchema;
+use super::{extract_string_field, format_output, process, Tool, ToolContext};
+
+pub struct CompileCheckTool;
+
+#[async_trait]
+impl Tool for CompileCheckTool {
+    fn name(&self) -> &'static str {
+        "compile_check"
+    }
+
+    fn description(&self) -> &'static str {
+        "Check if project compiles or runs without error."
+    }
+
+    fn input_schema(&self) -> InputSchema {
+        InputSchema::object(
+            &[("cmd", "string", "The command to check for compiler/interpreter errors.")],
+            &["cmd"],
+        )
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> Result<String, anyhow::Error> {
+        let check_cmd = extract_string_field(input, "cmd")?;
+        ctx.check_command(check_cmd).await?;
+        // Long running programs (servers) never exit on their own, so give them a few
+        // seconds to surface startup errors and then stop them, along with anything they
+        // started.
+        let output = process::run_bash(&format!("{} & sleep 5; kill $!", check_cmd), &ctx.root_path, true)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute command: {}", e))?;
+
+        Ok(format_output(&output))
+    }
+}
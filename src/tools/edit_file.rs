@@ -0,0 +1,210 @@
+use async_trait::async_trait;
+use diffy::{DiffOptions, Line, Patch};
+use serde::Deserialize;
+
+use crate::inference::tools::InputSchema;
+use super::{extract_string_field, Tool, ToolContext};
+
+/// A search/replace block of an `edit_file` call.
+#[derive(Debug, Deserialize)]
+struct Edit {
+    old_string: String,
+    new_string: String,
+}
+
+/// Changes part of a file, so the model doesn't have to send the whole file back for a
+/// small change.
+pub struct EditFileTool;
+
+#[async_trait]
+impl Tool for EditFileTool {
+    fn name(&self) -> &'static str {
+        "edit_file"
+    }
+
+    fn description(&self) -> &'static str {
+        "Change part of a file at path relative to root directory of project, with either search/replace edits or a unified diff.  \
+        Every old_string must appear exactly once in the file, include enough surrounding lines to make it unique.  \
+        Nothing is changed if any edit doesn't apply.  Returns the diff of the change."
+    }
+
+    fn input_schema(&self) -> InputSchema {
+        InputSchema::object(
+            &[
+                ("path", "string", "The file path relative to the project root directory"),
+                ("edits", "array", "Search/replace blocks, applied in order"),
+                ("diff", "string", "Unified diff hunks to apply instead of edits"),
+            ],
+            &["path"],
+        ).with_items("edits", InputSchema::object(
+            &[
+                ("old_string", "string", "The exact text to replace"),
+                ("new_string", "string", "The text to replace it with"),
+            ],
+            &["old_string", "new_string"],
+        ))
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> Result<String, anyhow::Error> {
+        let file_path = extract_string_field(input, "path")?;
+        let full_path = ctx.resolve(file_path)?;
+        let original = match tokio::fs::read_to_string(&full_path).await {
+            Ok(content) => content,
+            Err(e) => return Ok(format!("Error reading file {:?}: {:?}.", file_path, e)),
+        };
+
+        // Some models send every optional field, leaving the unused one null
+        let field = |name: &str| input.get(name).filter(|value| !value.is_null());
+        let edited = match (field("edits"), field("diff")) {
+            (Some(edits), None) => apply_edits(&original, edits),
+            (None, Some(_)) => extract_string_field(input, "diff").and_then(|diff| apply_diff(&original, diff)),
+            _ => Err(anyhow::anyhow!("Provide either 'edits' or 'diff'")),
+        };
+        let edited = match edited {
+            Ok(edited) => edited,
+            Err(e) => return Ok(format!("Error editing file {:?}: {}.  The file was not changed.", file_path, e)),
+        };
+
+        if let Err(e) = tokio::fs::write(&full_path, &edited).await {
+            return Ok(format!("Error writing to file {:?}: {:?}.", file_path, e));
+        }
+        let patch = DiffOptions::new()
+            .set_original_filename(format!("a/{}", file_path))
+            .set_modified_filename(format!("b/{}", file_path))
+            .create_patch(&original, &edited);
+        Ok(format!("Successfully edited file {:?}.\n{}", file_path, patch))
+    }
+}
+
+fn apply_edits(original: &str, edits: &serde_json::Value) -> Result<String, anyhow::Error> {
+    let edits: Vec<Edit> = serde_json::from_value(edits.clone())
+        .map_err(|e| anyhow::anyhow!("'edits' must be a list of old_string/new_string pairs: {}", e))?;
+    if edits.is_empty() {
+        return Err(anyhow::anyhow!("'edits' is empty"));
+    }
+
+    let mut content = original.to_string();
+    for (i, edit) in edits.iter().enumerate() {
+        if edit.old_string.is_empty() {
+            return Err(anyhow::anyhow!("old_string of edit {} is empty", i + 1));
+        }
+        match content.matches(&edit.old_string).count() {
+            0 => return Err(anyhow::anyhow!("old_string of edit {} was not found", i + 1)),
+            1 => content = content.replacen(&edit.old_string, &edit.new_string, 1),
+            n => return Err(anyhow::anyhow!(
+                "old_string of edit {} appears {} times, add surrounding lines to make it unique", i + 1, n
+            )),
+        }
+    }
+    Ok(content)
+}
+
+fn apply_diff(original: &str, diff: &str) -> Result<String, anyhow::Error> {
+    let patch = Patch::from_str(diff)
+        .map_err(|e| anyhow::anyhow!("Invalid diff: {}", e))?;
+    if patch.hunks().is_empty() {
+        return Err(anyhow::anyhow!("The diff has no hunks"));
+    }
+    // Like the edits, every hunk has to point at one place, diffy would take the first
+    // place its lines fit
+    for (i, hunk) in patch.hunks().iter().enumerate() {
+        let old_lines = hunk.lines().iter()
+            .filter_map(|line| match line {
+                Line::Context(text) | Line::Delete(text) => Some(*text),
+                Line::Insert(_) => None,
+            })
+            .collect::<String>();
+        if old_lines.is_empty() {
+            continue;
+        }
+        match count_line_occurrences(original, &old_lines) {
+            0 => return Err(anyhow::anyhow!("The context and removed lines of hunk {} were not found", i + 1)),
+            1 => {},
+            n => return Err(anyhow::anyhow!(
+                "Hunk {} is ambiguous, its context and removed lines appear {} times, add surrounding lines to make it unique", i + 1, n
+            )),
+        }
+    }
+    diffy::apply(original, &patch)
+        .map_err(|e| anyhow::anyhow!("The diff does not apply, {}", e))
+}
+
+/// How often `lines` appears in `text` starting at the beginning of a line, overlapping
+/// occurrences included.
+fn count_line_occurrences(text: &str, lines: &str) -> usize {
+    (0..text.len())
+        .filter(|&i| i == 0 || text.as_bytes()[i - 1] == b'\n')
+        .filter(|&i| text[i..].starts_with(lines))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX_JS: &str = "const express = require('express');\nconst app = express();\n\napp.get('/', (req, res) => res.send('ok'));\n\napp.listen(3000);\n";
+
+    #[tokio::test]
+    async fn test_edits_and_diffs_apply_only_when_unambiguous() -> Result<(), anyhow::Error> {
+        let project = tempfile::tempdir()?;
+        let path = project.path().join("index.js");
+        std::fs::write(&path, INDEX_JS)?;
+        let ctx = ToolContext::new(project.path().to_path_buf());
+
+        let output = EditFileTool.execute(&serde_json::json!({
+            "path": "index.js",
+            "edits": [{"old_string": "app.listen(3000);", "new_string": "app.get('/ping', (req, res) => res.send('pong'));\n\napp.listen(3000);"}],
+        }), &ctx).await?;
+        assert!(output.contains("+app.get('/ping'"), "{}", output);
+        assert!(output.contains("--- a/index.js"));
+        let with_ping = std::fs::read_to_string(&path)?;
+        assert_eq!(with_ping, INDEX_JS.replace("app.listen", "app.get('/ping', (req, res) => res.send('pong'));\n\napp.listen"));
+
+        // The second edit is ambiguous, so the first isn't applied either
+        let output = EditFileTool.execute(&serde_json::json!({
+            "path": "index.js",
+            "edits": [
+                {"old_string": "3000", "new_string": "8080"},
+                {"old_string": "app.get(", "new_string": "app.post("},
+            ],
+        }), &ctx).await?;
+        assert!(output.contains("edit 2 appears 2 times"), "{}", output);
+        let output = EditFileTool.execute(&serde_json::json!({
+            "path": "index.js",
+            "edits": [{"old_string": "app.listen(8080);", "new_string": ""}],
+        }), &ctx).await?;
+        assert!(output.contains("edit 1 was not found"), "{}", output);
+        assert_eq!(std::fs::read_to_string(&path)?, with_ping);
+
+        // Line numbers of the hunk are off, the context still finds it
+        let output = EditFileTool.execute(&serde_json::json!({
+            "path": "index.js",
+            "diff": "--- a/index.js\n+++ b/index.js\n@@ -1,2 +1,2 @@\n \n-app.listen(3000);\n+app.listen(process.env.PORT || 3000);\n",
+        }), &ctx).await?;
+        assert!(output.starts_with("Successfully edited"), "{}", output);
+        assert!(std::fs::read_to_string(&path)?.ends_with("app.listen(process.env.PORT || 3000);\n"));
+
+        let output = EditFileTool.execute(&serde_json::json!({
+            "path": "index.js",
+            "edits": [{"old_string": "process.env.PORT || 3000", "new_string": "process.env.PORT || 8080"}],
+            "diff": null,
+        }), &ctx).await?;
+        assert!(output.starts_with("Successfully edited"), "{}", output);
+
+        let output = EditFileTool.execute(&serde_json::json!({
+            "path": "index.js",
+            "diff": "@@ -1,1 +1,1 @@\n-const koa = require('koa');\n+const Koa = require('koa');\n",
+        }), &ctx).await?;
+        assert!(output.contains("were not found"), "{}", output);
+
+        // The handler block appears twice, the hunk could go to either
+        std::fs::write(&path, "app.get('/a', (req, res) => {\n  res.send('ok');\n});\n\napp.get('/b', (req, res) => {\n  res.send('ok');\n});\n")?;
+        let output = EditFileTool.execute(&serde_json::json!({
+            "path": "index.js",
+            "diff": "@@ -1,2 +1,2 @@\n-  res.send('ok');\n+  res.send('pong');\n });\n",
+        }), &ctx).await?;
+        assert!(output.contains("Hunk 1 is ambiguous, its context and removed lines appear 2 times"), "{}", output);
+        assert!(!std::fs::read_to_string(&path)?.contains("pong"));
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+
+use crate::inference::tools::InputSchema;
+use super::{extract_string_field, format_output, process, Tool, ToolContext};
+
+pub struct ExecuteTool;
+
+#[async_trait]
+impl Tool for ExecuteTool {
+    fn name(&self) -> &'static str {
+        "execute"
+    }
+
+    fn description(&self) -> &'static str {
+        "Execute bash statements as a single string."
+    }
+
+    fn input_schema(&self) -> InputSchema {
+        InputSchema::object(
+            &[("statement", "string", "The bash statement to be executed.")],
+            &["statement"],
+        )
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> Result<String, anyhow::Error> {
+        let statement = extract_string_field(input, "statement")?;
+        ctx.check_command(statement).await?;
+        let output = process::run_bash(statement, &ctx.root_path, false)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute command: {}", e))?;
+
+        Ok(format_output(&output))
+    }
+}
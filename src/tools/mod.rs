@@ -0,0 +1,153 @@
+pub mod compile_check;
+pub mod edit_file;
+pub mod execute;
+pub mod policy;
+mod process;
+pub mod read_file;
+pub mod sandbox;
+pub mod search;
+pub mod write_file;
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::config::{CommandAction, CommandPolicy};
+use crate::inference::tools::InputSchema;
+
+pub use compile_check::CompileCheckTool;
+pub use edit_file::EditFileTool;
+pub use execute::ExecuteTool;
+pub use policy::{Approvals, CommandBlocked};
+pub use read_file::ReadFileTool;
+pub use search::SearchTool;
+pub use write_file::WriteFileTool;
+
+/// Everything a tool needs to know about the project it is running against.
+#[derive(Debug, Clone)]
+pub struct ToolContext {
+    pub root_path: PathBuf,
+    /// Directories outside the project that file tools may use as well
+    pub allowed_dirs: Vec<PathBuf>,
+    pub commands: CommandPolicy,
+    pub approvals: Approvals,
+}
+
+impl ToolContext {
+    pub fn new(root_path: PathBuf) -> Self {
+        ToolContext {
+            root_path,
+            allowed_dirs: Vec::new(),
+            commands: CommandPolicy::default(),
+            approvals: Approvals::default(),
+        }
+    }
+
+    /// Fails with `CommandBlocked` unless the command policy lets `command` run, waiting
+    /// for the user when it asks for approval, at most `approval_timeout_secs`.
+    pub async fn check_command(&self, command: &str) -> Result<(), anyhow::Error> {
+        let verdict = policy::evaluate(&self.commands, command);
+        let answer = match verdict.action {
+            CommandAction::Allow => return Ok(()),
+            CommandAction::Ask => self.approvals.request(command, verdict.clone(), self.commands.approval_timeout()).await,
+            CommandAction::Deny => None,
+        };
+        if answer == Some(true) {
+            return Ok(());
+        }
+        let declined = verdict.action == CommandAction::Ask;
+        Err(CommandBlocked { command: command.to_string(), declined, timed_out: declined && answer.is_none(), verdict }.into())
+    }
+
+    /// The file at `path` from the model, if it is inside the project or an allowed
+    /// directory.  Every tool touching files goes through this.
+    pub fn resolve(&self, path: &str) -> Result<PathBuf, anyhow::Error> {
+        sandbox::resolve(&self.root_path, &self.allowed_dirs, path)
+    }
+}
+
+/// A tool the model can call.  Providers only ever see the name, description and
+/// schema, which they serialize into their own wire format, while `execute` holds
+/// the actual behavior.
+#[async_trait]
+pub trait Tool: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn input_schema(&self) -> InputSchema;
+    /// Whether the tool leaves the project untouched, so it can run alongside other
+    /// read-only tools.
+    fn is_read_only(&self) -> bool {
+        false
+    }
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> Result<String, anyhow::Error>;
+}
+
+pub struct ToolRegistry {
+    tools: Vec<Box<dyn Tool>>,
+}
+
+impl Default for ToolRegistry {
+    fn default() -> Self {
+        let mut registry = ToolRegistry::empty();
+        registry.register(ReadFileTool);
+        registry.register(SearchTool);
+        registry.register(WriteFileTool);
+        registry.register(EditFileTool);
+        registry.register(ExecuteTool);
+        registry.register(CompileCheckTool);
+        registry
+    }
+}
+
+impl ToolRegistry {
+    pub fn empty() -> Self {
+        ToolRegistry { tools: Vec::new() }
+    }
+
+    /// Registers a tool, replacing any previously registered tool with the same name.
+    pub fn register<T: Tool + 'static>(&mut self, tool: T) {
+        self.tools.retain(|t| t.name() != tool.name());
+        self.tools.push(Box::new(tool));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Tool> {
+        self.tools.iter()
+            .find(|t| t.name() == name)
+            .map(|t| t.as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Tool> {
+        self.tools.iter().map(|t| t.as_ref())
+    }
+}
+
+pub fn extract_string_field<'a>(
+    input: &'a serde_json::Value,
+    field_name: &str
+) -> Result<&'a str, anyhow::Error> {
+    input.get(field_name)
+        .ok_or_else(|| anyhow::anyhow!("Missing '{}' field in tool input: {:?}", field_name, input))?
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("'{}' field is not a string: {:?}", field_name, input.get(field_name)))
+}
+
+/// An optional number like a line or a count, which some models send as a string.
+pub fn extract_usize_field(input: &serde_json::Value, field_name: &str) -> Result<Option<usize>, anyhow::Error> {
+    match input.get(field_name) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(number)) => number.trim().parse().map(Some)
+            .map_err(|_| anyhow::anyhow!("'{}' field is not a number: {:?}", field_name, number)),
+        Some(number) => number.as_u64().map(|number| Some(number as usize))
+            .ok_or_else(|| anyhow::anyhow!("'{}' field is not a number: {:?}", field_name, number)),
+    }
+}
+
+/// Formats the output of a finished process the way every command-running tool reports it.
+pub fn format_output(output: &std::process::Output) -> String {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    format!("Stdout:
+{}
+Stderr:
+{}", stdout, stderr)
+}
@@ -0,0 +1,109 @@
+use std::io;
+use std::path::Path;
+use std::process::{Output, Stdio};
+
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+/// Runs `script` with bash in `dir`, in a process group of its own.  When the returned
+/// future is dropped, as on `/cancel`, the whole group is killed, not only bash, so the
+/// other commands of `a && b` or a backgrounded `cmd &` stop as well.  With
+/// `stop_leftovers` the group is also killed once bash exits, for scripts that leave
+/// programs running which aren't wanted afterwards.
+pub(super) async fn run_bash(script: &str, dir: &Path, stop_leftovers: bool) -> io::Result<Output> {
+    let mut command = Command::new("bash");
+    command.arg("-c")
+        .arg(script)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(unix)]
+    command.process_group(0);
+
+    let mut child = command.spawn()?;
+    let mut group = ProcessGroup(child.id());
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+
+    // Output is read while waiting, leftovers holding on to the pipes would otherwise
+    // keep it from ending
+    let wait = async {
+        let status = child.wait().await;
+        if stop_leftovers {
+            group.kill();
+        }
+        status
+    };
+    let read_stdout = async {
+        let mut buffer = Vec::new();
+        stdout.read_to_end(&mut buffer).await.map(|_| buffer)
+    };
+    let read_stderr = async {
+        let mut buffer = Vec::new();
+        stderr.read_to_end(&mut buffer).await.map(|_| buffer)
+    };
+    let (status, stdout, stderr) = tokio::try_join!(wait, read_stdout, read_stderr)?;
+
+    group.0 = None;
+    Ok(Output { status, stdout, stderr })
+}
+
+/// The process group led by a child, killed when dropped until it is cleared.
+struct ProcessGroup(Option<u32>);
+
+impl ProcessGroup {
+    fn kill(&self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.0 {
+            // SAFETY: kill only sends a signal, the negative pid addresses the group
+            unsafe {
+                libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+            }
+        }
+    }
+}
+
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Whether `pid` is alive, zombies waiting to be reaped count as gone.
+    #[cfg(target_os = "linux")]
+    fn is_running(pid: &str) -> bool {
+        std::fs::read_to_string(format!("/proc/{}/stat", pid))
+            .is_ok_and(|stat| !stat.rsplit(") ").next().unwrap_or_default().starts_with('Z'))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_cancel_kills_the_whole_process_group() -> Result<(), anyhow::Error> {
+        let dir = tempfile::tempdir()?;
+        let script = "sleep 60 & echo $! > background.pid; sleep 60 && echo never";
+        let cancelled = tokio::time::timeout(Duration::from_millis(500), run_bash(script, dir.path(), false)).await;
+        assert!(cancelled.is_err());
+
+        let pid = std::fs::read_to_string(dir.path().join("background.pid"))?;
+        let pid = pid.trim();
+        for _ in 0..50 {
+            if !is_running(pid) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(!is_running(pid), "sleep {} survived the cancel", pid);
+
+        // Leftovers holding the output open are stopped once the script is done
+        let output = run_bash("(sleep 60; echo late) & echo started", dir.path(), true).await?;
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "started\n");
+        Ok(())
+    }
+}
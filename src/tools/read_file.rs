@@ -0,0 +1,128 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+
+use crate::inference::tools::InputSchema;
+use super::{extract_string_field, extract_usize_field, Tool, ToolContext};
+
+/// Most of a file returned by one call, about 12k tokens of code
+const MAX_OUTPUT_BYTES: usize = 48 * 1024;
+/// How far into a file to look for NUL bytes, the same heuristic git uses
+const BINARY_CHECK_BYTES: usize = 8000;
+
+pub struct ReadFileTool;
+
+#[async_trait]
+impl Tool for ReadFileTool {
+    fn name(&self) -> &'static str {
+        "read_file"
+    }
+
+    fn description(&self) -> &'static str {
+        "Read file using path relative to root directory of project, optionally only the lines from start_line to end_line.  \
+        Every line is prefixed with its line number and a tab, which are not part of the file.  \
+        Long files are cut off with a hint where to continue reading."
+    }
+
+    fn input_schema(&self) -> InputSchema {
+        InputSchema::object(
+            &[
+                ("path", "string", "The file path relative to the project root directory"),
+                ("start_line", "integer", "First line to read, starting at 1"),
+                ("end_line", "integer", "Last line to read, inclusive"),
+            ],
+            &["path"],
+        )
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> Result<String, anyhow::Error> {
+        let file_path = extract_string_field(input, "path")?;
+        let start_line = extract_usize_field(input, "start_line")?;
+        let end_line = extract_usize_field(input, "end_line")?;
+        let full_path = ctx.resolve(file_path)?;
+        match tokio::fs::read(&full_path).await {
+            Ok(bytes) => Ok(format_lines(file_path, &bytes, start_line, end_line)),
+            Err(e) => Ok(format!("Error reading file {:?}: {:?}.", file_path, e)),
+        }
+    }
+}
+
+/// Whether `bytes` look like the contents of a binary file rather than text.
+pub(super) fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_CHECK_BYTES)].contains(&0)
+}
+
+/// The requested lines numbered like `cat -n`, cut off at `MAX_OUTPUT_BYTES`.
+fn format_lines(file_path: &str, bytes: &[u8], start_line: Option<usize>, end_line: Option<usize>) -> String {
+    if is_binary(bytes) {
+        return format!("File {:?} is binary ({} bytes), its contents can't be shown.", file_path, bytes.len());
+    }
+    let Ok(content) = std::str::from_utf8(bytes) else {
+        return format!("File {:?} is not UTF-8 text ({} bytes), its contents can't be shown.", file_path, bytes.len());
+    };
+
+    let lines = content.lines().collect::<Vec<_>>();
+    let total = lines.len();
+    let start = start_line.unwrap_or(1).max(1);
+    let end = end_line.unwrap_or(total).min(total);
+    if total == 0 {
+        return format!("File {:?} is empty.", file_path);
+    }
+    if start > end {
+        return format!("File {:?} has {} lines, there are no lines {} to {}.", file_path, total, start, end_line.unwrap_or(total));
+    }
+
+    let width = end.to_string().len();
+    let mut output = String::new();
+    let mut last = start - 1;
+    for (number, line) in lines[start - 1..end].iter().enumerate().map(|(i, line)| (start + i, line)) {
+        let numbered = format!("{:>width$}\t{}\n", number, line, width = width);
+        if output.len() + numbered.len() > MAX_OUTPUT_BYTES {
+            if output.is_empty() {
+                // A single line over the limit, e.g. minified code, is cut instead
+                let cut = (0..=MAX_OUTPUT_BYTES).rev().find(|&i| numbered.is_char_boundary(i)).unwrap_or(0);
+                output.push_str(&numbered[..cut]);
+                output.push('\n');
+                last = number;
+            }
+            break;
+        }
+        output.push_str(&numbered);
+        last = number;
+    }
+
+    let _ = write!(output, "(lines {}-{} of {}", start, last, total);
+    if last < end {
+        let _ = write!(output, ", output truncated, continue with start_line {}", last + 1);
+    }
+    output.push(')');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_numbered_ranges_within_limit() {
+        let content = (1..=12).map(|i| format!("line {}", i)).collect::<Vec<_>>().join("\n");
+        assert_eq!(
+            format_lines("notes.txt", content.as_bytes(), Some(9), Some(10)),
+            " 9\tline 9\n10\tline 10\n(lines 9-10 of 12)",
+        );
+        assert!(format_lines("notes.txt", content.as_bytes(), None, None).starts_with(" 1\tline 1\n"));
+        assert!(format_lines("notes.txt", content.as_bytes(), Some(13), None).contains("has 12 lines"));
+
+        let big = ("x".repeat(1000) + "\n").repeat(100);
+        let output = format_lines("big.txt", big.as_bytes(), None, None);
+        assert!(output.len() < MAX_OUTPUT_BYTES + 100);
+        assert!(output.ends_with("(lines 1-48 of 100, output truncated, continue with start_line 49)"), "{}", &output[output.len() - 80..]);
+
+        assert!(format_lines("logo.png", &[0x89, b'P', b'N', b'G', 0, 0], None, None).contains("is binary (6 bytes)"));
+        assert!(format_lines("latin1.txt", b"caf\xe9\n", None, None).contains("not UTF-8"));
+    }
+}
@@ -0,0 +1,99 @@
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Resolves `path`, relative to `root` or absolute, to the file a tool may touch.  Symlinks
+/// are followed before checking that the file is inside `root` or one of `allowed_dirs`,
+/// and git's own files are off limits.  Files that don't exist yet resolve through their
+/// closest existing parent.
+pub fn resolve(root: &Path, allowed_dirs: &[PathBuf], path: &str) -> Result<PathBuf, anyhow::Error> {
+    let root = root.canonicalize()
+        .map_err(|e| anyhow::anyhow!("Project root {} is not accessible: {}", root.display(), e))?;
+    let resolved = canonicalize_lenient(&root.join(path))
+        .map_err(|e| anyhow::anyhow!("Path {:?} can't be used: {}", path, e))?;
+
+    if let Ok(relative) = resolved.strip_prefix(&root) {
+        if relative.components().any(|component| component.as_os_str() == ".git") {
+            return Err(anyhow::anyhow!("Path {:?} is inside git's internal files, which tools may not touch", path));
+        }
+        return Ok(resolved);
+    }
+    let allowed = allowed_dirs.iter()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .any(|dir| resolved.starts_with(dir));
+    if allowed {
+        return Ok(resolved);
+    }
+    Err(anyhow::anyhow!(
+        "Path {:?} resolves to {} which is outside the project, tools may only use files inside {}",
+        path, resolved.display(), root.display()
+    ))
+}
+
+/// Like `canonicalize`, but the last components may not exist yet.
+fn canonicalize_lenient(path: &Path) -> io::Result<PathBuf> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    let mut resolved = loop {
+        match existing.canonicalize() {
+            Ok(resolved) => break resolved,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                // A symlink to a missing file would be created wherever it points
+                if existing.symlink_metadata().is_ok() {
+                    return Err(io::Error::other("it is a symlink to a file that doesn't exist"));
+                }
+                missing.push(existing.file_name().ok_or(e)?);
+                existing = existing.parent().ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+            },
+            Err(e) => return Err(e),
+        }
+    };
+
+    for component in missing.iter().rev().map(|name| Path::new(name).components().next()) {
+        match component {
+            Some(Component::Normal(name)) => resolved.push(name),
+            _ => return Err(io::Error::other("it goes through a directory that doesn't exist")),
+        }
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths_stay_in_project_or_allowed_dirs() -> Result<(), anyhow::Error> {
+        let workspace = tempfile::tempdir()?;
+        let root = workspace.path().join("project");
+        let shared = workspace.path().join("shared");
+        std::fs::create_dir_all(root.join("src"))?;
+        std::fs::create_dir_all(root.join(".git"))?;
+        std::fs::create_dir_all(&shared)?;
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n")?;
+        std::fs::write(shared.join("notes.md"), "# notes\n")?;
+        let root_dir = root.canonicalize()?;
+
+        assert_eq!(resolve(&root, &[], "src/main.rs")?, root_dir.join("src/main.rs"));
+        assert_eq!(resolve(&root, &[], "./src/../src/new/lib.rs")?, root_dir.join("src/new/lib.rs"));
+        assert_eq!(resolve(&root, &[], &root_dir.join("README.md").to_string_lossy())?, root_dir.join("README.md"));
+
+        let escapes = ["../shared/notes.md", "/etc/passwd", "src/../../shared/notes.md", ".git/config", "src/../.git/hooks/pre-commit"];
+        for path in escapes {
+            assert!(resolve(&root, &[], path).is_err(), "{} was allowed", path);
+        }
+        let allowed = [shared.clone()];
+        assert!(resolve(&root, &allowed, "../shared/notes.md").is_ok());
+        assert!(resolve(&root, &allowed, "../shared/../elsewhere.md").is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&shared, root.join("linked"))?;
+            std::os::unix::fs::symlink(workspace.path().join("missing.md"), root.join("dangling.md"))?;
+            let error = resolve(&root, &[], "linked/notes.md").unwrap_err();
+            assert!(error.to_string().contains("outside the project"), "{}", error);
+            assert!(resolve(&root, &[], "dangling.md").is_err());
+            assert!(resolve(&root, &allowed, "linked/notes.md").is_ok());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use globset::{Glob, GlobMatcher};
+use regex::{Regex, RegexBuilder};
+
+use crate::inference::tools::InputSchema;
+use crate::tree::GitTree;
+use super::read_file::is_binary;
+use super::{extract_string_field, extract_usize_field, Tool, ToolContext};
+
+const DEFAULT_MAX_RESULTS: usize = 100;
+const MAX_RESULTS: usize = 500;
+const MAX_CONTEXT_LINES: usize = 10;
+/// Matched lines longer than this are cut, they are usually minified or generated code
+const MAX_LINE_CHARS: usize = 300;
+
+/// Searches the files of the project without leaving the process, so it works without
+/// grep or ripgrep installed.
+pub struct SearchTool;
+
+/// What to look for and where, taken from the tool input.
+struct Query {
+    regex: Regex,
+    glob: Option<GlobMatcher>,
+    context: usize,
+    max_results: usize,
+}
+
+#[async_trait]
+impl Tool for SearchTool {
+    fn name(&self) -> &'static str {
+        "search"
+    }
+
+    fn description(&self) -> &'static str {
+        "Search the project's files, except those ignored by git, for a regex or literal text.  \
+        Returns matching lines as path:line:text, with context lines as path-line-text.  \
+        Use it to find where something is defined or used before reading whole files."
+    }
+
+    fn input_schema(&self) -> InputSchema {
+        InputSchema::object(
+            &[
+                ("pattern", "string", "Regular expression, or the exact text to find when literal is true"),
+                ("literal", "boolean", "Match pattern as plain text instead of a regex"),
+                ("case_insensitive", "boolean", "Ignore case when matching"),
+                ("glob", "string", "Only search paths matching this glob, e.g. src/**/*.rs or *.ts"),
+                ("context", "integer", "Lines to show before and after each match, at most 10"),
+                ("max_results", "integer", "Most matching lines to return, 100 by default and at most 500"),
+            ],
+            &["pattern"],
+        )
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> Result<String, anyhow::Error> {
+        let query = match parse_query(input) {
+            Ok(query) => query,
+            Err(e) => return Ok(format!("Invalid search: {}", e)),
+        };
+        let ctx = ctx.clone();
+        tokio::task::spawn_blocking(move || search(&ctx, &query)).await?
+    }
+}
+
+fn parse_query(input: &serde_json::Value) -> Result<Query, anyhow::Error> {
+    let pattern = extract_string_field(input, "pattern")?;
+    let literal = input.get("literal").and_then(|v| v.as_bool()).unwrap_or(false);
+    let case_insensitive = input.get("case_insensitive").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    let regex = RegexBuilder::new(&if literal { regex::escape(pattern) } else { pattern.to_string() })
+        .case_insensitive(case_insensitive)
+        .build()?;
+    let glob = match input.get("glob").and_then(|v| v.as_str()).filter(|glob| !glob.is_empty()) {
+        Some(glob) => Some(Glob::new(glob)?.compile_matcher()),
+        None => None,
+    };
+
+    Ok(Query {
+        regex,
+        glob,
+        context: extract_usize_field(input, "context")?.unwrap_or(0).min(MAX_CONTEXT_LINES),
+        max_results: extract_usize_field(input, "max_results")?.unwrap_or(DEFAULT_MAX_RESULTS).clamp(1, MAX_RESULTS),
+    })
+}
+
+fn search(ctx: &ToolContext, query: &Query) -> Result<String, anyhow::Error> {
+    let mut output = String::new();
+    let mut matches = 0;
+    let mut files = 0;
+    let mut truncated = false;
+
+    for path in GitTree::list_files(&ctx.root_path)? {
+        if query.glob.as_ref().is_some_and(|glob| !glob.is_match(&path)) {
+            continue;
+        }
+        // Symlinks out of the project, deleted but still tracked files, binaries and other
+        // encodings are skipped
+        let Ok(full_path) = ctx.resolve(&path) else { continue };
+        let Ok(bytes) = std::fs::read(full_path) else { continue };
+        if is_binary(&bytes) {
+            continue;
+        }
+        let Ok(content) = std::str::from_utf8(&bytes) else { continue };
+
+        let lines = content.lines().collect::<Vec<_>>();
+        let matched = lines.iter().enumerate()
+            .filter(|(_, line)| query.regex.is_match(line))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        if matched.is_empty() {
+            continue;
+        }
+        if matches == query.max_results {
+            truncated = true;
+            break;
+        }
+        let matched = if matches + matched.len() > query.max_results {
+            truncated = true;
+            &matched[..query.max_results - matches]
+        } else {
+            &matched[..]
+        };
+        files += 1;
+        matches += matched.len();
+
+        // Lines to show with whether they matched, overlapping context is merged
+        let mut shown = BTreeMap::new();
+        for &i in matched {
+            let first = i.saturating_sub(query.context);
+            let last = (i + query.context).min(lines.len() - 1);
+            for line in first..=last {
+                *shown.entry(line).or_insert(false) |= line == i;
+            }
+        }
+
+        if !output.is_empty() {
+            output.push_str("--\n");
+        }
+        let mut previous = None;
+        for (line, is_match) in shown {
+            if previous.is_some_and(|previous| previous + 1 != line) {
+                output.push_str("--\n");
+            }
+            let separator = if is_match { ':' } else { '-' };
+            let text = lines[line];
+            let text = match text.char_indices().nth(MAX_LINE_CHARS) {
+                Some((cut, _)) => format!("{}...", &text[..cut]),
+                None => text.to_string(),
+            };
+            let _ = writeln!(output, "{}{}{}{}{}", path, separator, line + 1, separator, text);
+            previous = Some(line);
+        }
+
+        if truncated {
+            break;
+        }
+    }
+
+    if matches == 0 {
+        return Ok(format!("No matches for {:?}.", query.regex.as_str()));
+    }
+    if truncated {
+        let _ = write!(output, "(first {} matches shown, narrow the pattern or glob to see the rest)", matches);
+    } else {
+        let _ = write!(output, "({} matches in {} files)", matches, files);
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_searches_files_git_does_not_ignore() -> Result<(), anyhow::Error> {
+        let project = tempfile::tempdir()?;
+        std::process::Command::new("git").arg("init").arg("-q").current_dir(project.path()).status()?;
+        std::fs::create_dir_all(project.path().join("src/routes"))?;
+        std::fs::create_dir_all(project.path().join("dist"))?;
+        std::fs::write(project.path().join(".gitignore"), "dist/\n")?;
+        std::fs::write(project.path().join("index.js"), "const app = require('./src/app');\n\napp.listen(3000);\n")?;
+        std::fs::write(project.path().join("src/app.js"), "const express = require('express');\nconst app = express();\napp.use(require('./routes/ping'));\nmodule.exports = app;\n")?;
+        std::fs::write(project.path().join("src/routes/ping.js"), "// GET /ping\nmodule.exports = (req, res) => res.send('pong');\n")?;
+        std::fs::write(project.path().join("src/routes/café.js"), "// GET /café\nmodule.exports = (req, res) => res.send('crème');\n")?;
+        std::fs::write(project.path().join("dist/app.js"), "const app=require('express')();app.listen(3000);\n")?;
+        std::fs::write(project.path().join("logo.png"), [0x89, b'P', b'N', b'G', 0, b'a', b'p', b'p'])?;
+        let ctx = ToolContext::new(project.path().to_path_buf());
+
+        let output = SearchTool.execute(&serde_json::json!({"pattern": r"app\.(listen|use)"}), &ctx).await?;
+        assert_eq!(output, "index.js:3:app.listen(3000);\n--\nsrc/app.js:3:app.use(require('./routes/ping'));\n(2 matches in 2 files)");
+
+        let output = SearchTool.execute(&serde_json::json!({
+            "pattern": "REQUIRE(",
+            "literal": true,
+            "case_insensitive": true,
+            "glob": "src/**",
+            "context": 1,
+        }), &ctx).await?;
+        assert_eq!(output, concat!(
+            "src/app.js:1:const express = require('express');\n",
+            "src/app.js-2-const app = express();\n",
+            "src/app.js:3:app.use(require('./routes/ping'));\n",
+            "src/app.js-4-module.exports = app;\n",
+            "(2 matches in 1 files)",
+        ));
+
+        let output = SearchTool.execute(&serde_json::json!({"pattern": "app", "max_results": "2"}), &ctx).await?;
+        assert!(output.ends_with("(first 2 matches shown, narrow the pattern or glob to see the rest)"), "{}", output);
+        // The cap is reached with the last match of a file
+        let output = SearchTool.execute(&serde_json::json!({"pattern": "module.exports", "literal": true, "max_results": 1}), &ctx).await?;
+        assert_eq!(output, "src/app.js:4:module.exports = app;\n(first 1 matches shown, narrow the pattern or glob to see the rest)");
+        let output = SearchTool.execute(&serde_json::json!({"pattern": "crème"}), &ctx).await?;
+        assert_eq!(output, "src/routes/café.js:2:module.exports = (req, res) => res.send('crème');\n(1 matches in 1 files)");
+        assert!(SearchTool.execute(&serde_json::json!({"pattern": "koa"}), &ctx).await?.starts_with("No matches"));
+        assert!(SearchTool.execute(&serde_json::json!({"pattern": "app.use("}), &ctx).await?.starts_with("Invalid search"));
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+
+use crate::inference::tools::InputSchema;
+use super::{extract_string_field, Tool, ToolContext};
+
+pub struct WriteFileTool;
+
+#[async_trait]
+impl Tool for WriteFileTool {
+    fn name(&self) -> &'static str {
+        "write_file"
+    }
+
+    fn description(&self) -> &'static str {
+        "Write string to file at path relative to root directory of project."
+    }
+
+    fn input_schema(&self) -> InputSchema {
+        InputSchema::object(
+            &[
+                ("path", "string", "The file path relative to the project root directory"),
+                ("content", "string", "The content to write to the file"),
+            ],
+            &["path", "content"],
+        )
+    }
+
+    async fn execute(&self, input: &serde_json::Value, ctx: &ToolContext) -> Result<String, anyhow::Error> {
+        let content = extract_string_field(input, "content")?;
+        let file_path = extract_string_field(input, "path")?;
+        let full_path = ctx.resolve(file_path)?;
+        match tokio::fs::write(&full_path, content).await {
+            Ok(_) => Ok(format!("Successfully wrote content to file {:?}.", file_path)),
+            Err(e) => Ok(format!("Error writing to file {:?}: {:?}.", file_path, e)),
+        }
+    }
+}
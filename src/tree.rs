@@ -1,8 +1,6 @@
 use std::collections::BTreeMap;
 use std::process::Command;
-use std::path::PathBuf;
-use gix;
-use anyhow;
+use std::path::{Path, PathBuf};
 
 pub struct GitTree;
 
@@ -25,28 +23,39 @@ impl GitTree {
         Ok(root_path)
     }
 
-    pub fn get_tree() -> Result<String, anyhow::Error> {
-        let root = Self::get_git_root()?;
+    /// Paths, relative to `root`, of the tracked and untracked files that aren't ignored.
+    pub fn list_files(root: &Path) -> Result<Vec<String>, anyhow::Error> {
         let mut cmd = Command::new("git");
         let cmd = cmd.arg("ls-files")
             .arg("-o")
             .arg("--exclude-standard")
             .arg("-c")
-            .current_dir(&root);
+            // NUL separated paths aren't quoted, which non-ASCII names would otherwise be
+            .arg("-z")
+            .current_dir(root);
 
         let output = cmd.output()?;
 
         if !output.status.success() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
+            return Err(std::io::Error::other(
                 String::from_utf8_lossy(&output.stderr).to_string()
             ).into());
         }
 
-        let files: Vec<String> = String::from_utf8_lossy(&output.stdout)
-            .lines()
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|path| !path.is_empty())
             .map(String::from)
-            .collect();
+            .collect())
+    }
+
+    pub fn get_tree() -> Result<String, anyhow::Error> {
+        Self::get_tree_of(&Self::get_git_root()?)
+    }
+
+    /// The files of the repository at `root` drawn as a tree.
+    pub fn get_tree_of(root: &Path) -> Result<String, anyhow::Error> {
+        let files = Self::list_files(root)?;
 
         let mut tree = BTreeMap::new();
         for path in files {
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::inference::types::Usage;
+
+/// Price of a model in USD per million tokens.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    #[serde(default)]
+    pub cache_write: f64,
+    #[serde(default)]
+    pub cache_read: f64,
+}
+
+impl ModelPrice {
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_creation_input_tokens as f64 * self.cache_write
+            + usage.cache_read_input_tokens as f64 * self.cache_read)
+            / 1_000_000.0
+    }
+}
+
+/// Prices for commonly used models, used when `pprog.toml` has no `[pricing]` entry for
+/// the model.  Entries in the config always win.
+pub fn default_pricing() -> HashMap<String, ModelPrice> {
+    let price = |input, output, cache_write, cache_read| ModelPrice { input, output, cache_write, cache_read };
+    HashMap::from([
+        ("claude-3-5-haiku-latest".to_string(), price(0.80, 4.00, 1.00, 0.08)),
+        ("claude-3-5-haiku-20241022".to_string(), price(0.80, 4.00, 1.00, 0.08)),
+        ("claude-3-5-sonnet-latest".to_string(), price(3.00, 15.00, 3.75, 0.30)),
+        ("claude-3-5-sonnet-20241022".to_string(), price(3.00, 15.00, 3.75, 0.30)),
+        ("claude-3-opus-latest".to_string(), price(15.00, 75.00, 18.75, 1.50)),
+        ("gpt-4o".to_string(), price(2.50, 10.00, 0.0, 1.25)),
+        ("gpt-4o-mini".to_string(), price(0.15, 0.60, 0.0, 0.075)),
+        ("deepseek-chat".to_string(), price(0.27, 1.10, 0.0, 0.07)),
+        ("deepseek-reasoner".to_string(), price(0.55, 2.19, 0.0, 0.14)),
+    ])
+}
+
+/// Usage of one user request, which spans every model call made while the model works
+/// through its tool calls.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct TurnUsage {
+    pub model: String,
+    pub requests: usize,
+    pub usage: Usage,
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Default, Clone, PartialEq)]
+pub struct SessionUsage {
+    pub turns: Vec<TurnUsage>,
+    pub total: Usage,
+    pub total_cost_usd: f64,
+    /// Number of turns containing a model call without a known price, in which case
+    /// `total_cost_usd` underestimates the real cost.
+    pub unpriced_turns: usize,
+}
+
+impl SessionUsage {
+    pub fn start_turn(&mut self, model: &str) {
+        self.turns.push(TurnUsage {
+            model: model.to_string(),
+            requests: 0,
+            usage: Usage::default(),
+            cost_usd: Some(0.0),
+        });
+    }
+
+    pub fn record(&mut self, model: &str, usage: Usage, price: Option<&ModelPrice>) {
+        if self.turns.is_empty() {
+            self.start_turn(model);
+        }
+        let turn = self.turns.last_mut().unwrap();
+        let was_priced = turn.cost_usd.is_some();
+
+        turn.requests += 1;
+        turn.usage += usage;
+        turn.cost_usd = match (turn.cost_usd, price) {
+            (Some(cost), Some(price)) => Some(cost + price.cost(&usage)),
+            _ => None,
+        };
+
+        self.total += usage;
+        if let Some(price) = price {
+            self.total_cost_usd += price.cost(&usage);
+        }
+        if was_priced && turn.cost_usd.is_none() {
+            self.unpriced_turns += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns_accumulate_requests_and_cost() {
+        let price = ModelPrice { input: 3.0, output: 15.0, cache_write: 3.75, cache_read: 0.30 };
+        let usage = Usage {
+            input_tokens: 1_000,
+            output_tokens: 500,
+            cache_creation_input_tokens: 10_000,
+            cache_read_input_tokens: 0,
+        };
+
+        let mut session = SessionUsage::default();
+        session.start_turn("claude-3-5-sonnet-latest");
+        session.record("claude-3-5-sonnet-latest", usage, Some(&price));
+        session.record("claude-3-5-sonnet-latest", usage, Some(&price));
+        session.start_turn("unknown-model");
+        session.record("unknown-model", usage, None);
+
+        assert_eq!(session.turns.len(), 2);
+        assert_eq!(session.turns[0].requests, 2);
+        assert!((session.turns[0].cost_usd.unwrap() - 0.096).abs() < 1e-9);
+        assert_eq!(session.turns[1].cost_usd, None);
+        assert_eq!(session.unpriced_turns, 1);
+        assert_eq!(session.total.input_tokens, 3_000);
+        assert!((session.total_cost_usd - 0.096).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use pprog::chat::Chat;
+use pprog::inference::types::{ContentItem, Message, Role};
+
+#[tokio::test]
+async fn test_send_message_runs_the_tool_loop() -> Result<(), anyhow::Error> {
+    let project = common::project();
+    let mut chat = Chat::from_config(common::replay_config("add_ping.json")).await;
+    chat.root = Some(project.path().to_path_buf());
+
+    let mut reply = chat.send_message(Message {
+        role: Role::User,
+        content: vec![ContentItem::Text { text: "Add a GET /ping endpoint".to_string() }],
+        model: None,
+    }).await?;
+
+    let mut tool_calls = Vec::new();
+    while let Some(tool_use) = reply.content.iter().find(|item| matches!(item, ContentItem::ToolUse { .. })) {
+        let ContentItem::ToolUse { id, name, .. } = tool_use else { unreachable!() };
+        tool_calls.push(name.clone());
+        let result = chat.handle_tool_use(tool_use).await?;
+        reply = chat.send_message(Message {
+            role: Role::User,
+            content: vec![ContentItem::ToolResult { tool_use_id: id.clone(), content: result }],
+            model: None,
+        }).await?;
+    }
+
+    assert_eq!(tool_calls, ["read_file", "write_file"]);
+    assert_eq!(reply.content, vec![ContentItem::Text { text: "Added a GET /ping endpoint that responds with pong.".to_string() }]);
+    assert_eq!(reply.model.as_deref(), Some("claude-3-5-haiku-20241022"));
+    assert!(std::fs::read_to_string(project.path().join("index.js"))?.contains("app.get('/ping'"));
+    assert_eq!(chat.messages.len(), 6);
+    assert_eq!(chat.usage.turns.len(), 1);
+    assert_eq!(chat.usage.turns[0].requests, 3);
+    assert_eq!(chat.usage.total.output_tokens, 180);
+    Ok(())
+}
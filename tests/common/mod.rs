@@ -0,0 +1,30 @@
+use std::path::Path;
+use std::process::Command;
+
+use pprog::config::{ProjectConfig, ReplayConfig};
+use tempfile::TempDir;
+
+pub const INDEX_JS: &str = "const express = require('express');\nconst app = express();\napp.listen(3000);\n";
+
+/// A git repository holding a bare express server.
+pub fn project() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let status = Command::new("git").arg("init").arg("-q").current_dir(dir.path()).status().unwrap();
+    assert!(status.success());
+    std::fs::write(dir.path().join("index.js"), INDEX_JS).unwrap();
+    dir
+}
+
+/// Config answering from `tests/cassettes/<name>`.
+pub fn replay_config(name: &str) -> ProjectConfig {
+    let cassette = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/cassettes").join(name);
+    ProjectConfig {
+        provider: "replay".to_string(),
+        model: "claude-3-5-haiku-latest".to_string(),
+        replay: ReplayConfig {
+            cassette: cassette.to_string_lossy().to_string(),
+            ..ReplayConfig::default()
+        },
+        ..ProjectConfig::default()
+    }
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use actix_web::{test, web, App};
+use pprog::chat::Chat;
+use pprog::server::{self, AppState};
+use serde_json::{json, Value};
+
+#[actix_web::test]
+async fn test_chat_handler_bounces_tool_calls_through_the_client() {
+    let project = common::project();
+    let mut chat = Chat::from_config(common::replay_config("add_ping.json")).await;
+    chat.root = Some(project.path().to_path_buf());
+    let app = test::init_service(App::new().app_data(web::Data::new(AppState::new(chat))).configure(server::routes)).await;
+
+    let mut message = json!({"role": "user", "content": [{"type": "text", "text": "Add a GET /ping endpoint"}]});
+    let mut tool_results = 0;
+    let reply = loop {
+        let request = test::TestRequest::post().uri("/chat").set_json(json!({"message": message})).to_request();
+        let reply: Value = test::call_and_read_body_json(&app, request).await;
+        let reply = reply["message"].clone();
+
+        // The client answers tool calls by sending them back for the server to run
+        let tool_uses = reply["content"].as_array().unwrap().iter()
+            .filter(|item| item["type"] == "tool_use")
+            .collect::<Vec<_>>();
+        message = match tool_uses.is_empty() {
+            false => json!({"role": "assistant", "content": tool_uses}),
+            true if reply["role"] == "user" => {
+                tool_results += 1;
+                reply.clone()
+            },
+            true => break reply,
+        };
+    };
+
+    assert_eq!(tool_results, 2);
+    assert_eq!(reply["content"][0]["text"], "Added a GET /ping endpoint that responds with pong.");
+    assert_eq!(reply["model"], "claude-3-5-haiku-20241022");
+
+    let request = test::TestRequest::get().uri("/usage").to_request();
+    let usage: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(usage["turns"][0]["requests"], 3);
+}